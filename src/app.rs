@@ -1,6 +1,12 @@
-use std::{error::Error, path::Path};
-
-use crate::{cli::Cli, gdb::Gdb, ui::UiManager};
+use std::error::Error;
+
+use crate::{
+    cli::Cli,
+    gdb::Gdb,
+    rr::{ReplayEvent, RrReplay},
+    session::Session,
+    ui::UiManager,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Default, Deserialize, Serialize)]
@@ -13,6 +19,10 @@ pub struct Gdbr {
 
     #[serde(skip)]
     gdb: Option<Gdb>,
+
+    /// The replay GDB is connected to, dropped after GDB
+    #[serde(skip)]
+    rr: Option<RrReplay>,
 }
 
 impl Gdbr {
@@ -30,24 +40,147 @@ impl Gdbr {
         app.ui.setup_theme(&cc.egui_ctx);
         app.ui.setup_fonts(&cc.egui_ctx);
 
-        if let Some(cli) = cli
-            && let Some(executable) = cli.executable
-        {
-            // This will start the gdb process on first update call
-            app.ui.set_file_details(Path::new(&executable));
+        if let Some(cli) = cli {
+            let session = Session::from_cli(&cli);
+            app.cli = cli;
+
+            app.ui.load_project();
+            if let Some(profile) = &app.cli.profile {
+                app.ui.apply_profile(profile);
+            }
+
+            match session {
+                // This will start the gdb process on first update call
+                Some(Session::Launch(executable)) => {
+                    app.ui.apply_cli_launch(&executable, &app.cli);
+                    app.ui.set_file_details(&executable);
+                }
+                // There is no file to wait for, GDB finds the executable itself
+                Some(session) => app.start_session(&session, &cc.egui_ctx),
+                None => {}
+            }
         }
 
         app
     }
 
-    /// Spawn a new GDB process for the given file
-    pub fn spawn_gdb(&mut self, file_path: &Path) -> Result<(), Box<dyn Error>> {
+    /// Spawn a new GDB process for a session
+    pub fn spawn_gdb(
+        &mut self,
+        session: &Session,
+        ctx: &egui::Context,
+    ) -> Result<(), Box<dyn Error>> {
+        // Replace the previous session, including its replay
+        self.gdb = None;
+        self.rr = None;
+
         let mut args = self.cli.gdb_args.clone();
-        args.push(file_path.to_string_lossy().to_string());
-        self.gdb = Some(Gdb::new(args)?);
+        match session {
+            Session::Launch(file_path) => args.push(file_path.to_string_lossy().to_string()),
+            Session::Replay { trace, targets } => {
+                let rr = RrReplay::spawn(trace.as_deref(), targets)?;
+                args.extend(rr.gdb_args());
+                self.rr = Some(rr);
+            }
+            Session::Attach(_) => {}
+            Session::Core { executable, core } => {
+                if let Some(executable) = executable {
+                    args.push(executable.to_string_lossy().to_string());
+                }
+                args.push(format!("--core={}", core.to_string_lossy()));
+            }
+            Session::Remote { executable, .. } => {
+                if let Some(executable) = executable {
+                    args.push(executable.to_string_lossy().to_string());
+                }
+            }
+        }
+        self.gdb = Some(Gdb::new(args, ctx)?);
         Ok(())
     }
 
+    /// Spawn GDB for a session and report the outcome in the console
+    fn start_session(&mut self, session: &Session, ctx: &egui::Context) {
+        if let Err(e) = self.spawn_gdb(session, ctx) {
+            eprintln!("Failed to spawn GDB: {e}");
+            self.ui.add_error(format!("Failed to spawn GDB: {e}"));
+            return;
+        }
+
+        if let Session::Core {
+            executable: Some(executable),
+            ..
+        }
+        | Session::Remote {
+            executable: Some(executable),
+            ..
+        } = session
+        {
+            self.ui.set_picked_file(executable);
+        }
+
+        self.ui.set_gdb_available(true, ctx);
+        self.ui.set_reverse_available(self.rr.is_some());
+        match session {
+            Session::Launch(_) => self
+                .ui
+                .add_info("GDB process started successfully".to_owned()),
+            Session::Replay { .. } => {
+                if let Some(rr) = &self.rr {
+                    self.ui.add_info(format!(
+                        "GDB process started, replaying with rr on port {}",
+                        rr.port()
+                    ));
+                    self.ui.connect_remote(rr.target());
+                }
+            }
+            Session::Attach(pid) => {
+                self.ui
+                    .add_info(format!("GDB process started, attaching to {pid}"));
+                self.ui.send_command(format!("-target-attach {pid}"));
+            }
+            Session::Core { core, .. } => {
+                self.ui.add_info(format!(
+                    "GDB process started, inspecting {}",
+                    core.display()
+                ));
+                self.ui.load_core();
+            }
+            Session::Remote { target, .. } => {
+                self.ui.add_info(format!(
+                    "GDB process started, connecting to {}",
+                    target.address
+                ));
+                self.ui.connect_remote(target.clone());
+            }
+        }
+    }
+
+    /// Follow the replay, connecting GDB again when rr had to be restarted
+    fn poll_rr(&mut self) {
+        let connected = self.ui.is_remote_connected();
+        let Some(rr) = &mut self.rr else {
+            return;
+        };
+        match rr.poll(connected) {
+            Some(ReplayEvent::Restarted) => {
+                self.ui.add_warning(format!(
+                    "rr exited before GDB connected, replaying again on port {}",
+                    rr.port()
+                ));
+                self.ui.connect_remote(rr.target());
+            }
+            Some(ReplayEvent::Exited(status)) => {
+                // There is nothing left to connect to
+                self.ui.disable_remote_reconnect();
+                if !status.success() {
+                    self.ui.add_error(format!("rr replay failed: {status}"));
+                }
+            }
+            None => {}
+        }
+    }
+
     /// Get a reference to the GDB instance if it exists
     pub fn gdb(&self) -> Option<&Gdb> {
         self.gdb.as_ref()
@@ -68,23 +201,23 @@ impl eframe::App for Gdbr {
         ctx.set_zoom_factor(self.ui.zoom);
         self.ui.update(ctx);
         self.ui.show_menu_bar(ctx);
+        self.ui.show_status_bar(ctx);
         self.ui.show_dock_area(ctx);
 
         // Check if a file was just loaded and spawn GDB if needed
         if self.ui.was_file_just_loaded()
             && let Some(file_path) = self.ui.get_picked_file()
         {
-            let file_path = file_path.clone();
-            if let Err(e) = self.spawn_gdb(&file_path) {
-                eprintln!("Failed to spawn GDB: {e}");
-                self.ui.add_error(format!("Failed to spawn GDB: {e}"));
-            } else {
-                self.ui.set_gdb_available(true);
-                self.ui
-                    .add_info("GDB process started successfully".to_owned());
-            }
+            let session = Session::Launch(file_path.clone());
+            self.start_session(&session, ctx);
         }
 
+        if let Some(session) = self.ui.take_session_request() {
+            self.start_session(&session, ctx);
+        }
+
+        self.poll_rr();
+
         if let Some(gdb) = &self.gdb {
             self.ui.update_from_gdb(gdb);
 
@@ -93,6 +226,11 @@ impl eframe::App for Gdbr {
                 self.ui
                     .add_error(format!("Failed to process pending commands: {e}"));
             }
+
+            // Results of queries often queue more, which are sent on the next update
+            if self.ui.is_busy() {
+                ctx.request_repaint();
+            }
         }
     }
 }
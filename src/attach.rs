@@ -0,0 +1,159 @@
+use egui::{Grid, RichText, ScrollArea, TextEdit, Ui};
+use nix::unistd::{Uid, User};
+use std::collections::HashMap;
+
+/// A local process that can be attached to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub user: String,
+    pub command: String,
+}
+
+impl ProcessInfo {
+    /// Check if the pid, user or command contains a lowercase filter
+    fn matches(&self, filter: &str) -> bool {
+        filter.is_empty()
+            || self.pid.to_string().contains(filter)
+            || self.user.to_lowercase().contains(filter)
+            || self.command.to_lowercase().contains(filter)
+    }
+}
+
+/// List the processes in `/proc`, sorted by pid
+pub fn list_processes() -> std::io::Result<Vec<ProcessInfo>> {
+    let mut users: HashMap<u32, String> = HashMap::new();
+    let mut processes = Vec::new();
+
+    for entry in std::fs::read_dir("/proc")? {
+        let entry = entry?;
+        let Some(pid) = entry.file_name().to_str().and_then(|p| p.parse().ok()) else {
+            continue;
+        };
+        // The process may exit while it is being read
+        let Ok(status) = std::fs::read_to_string(entry.path().join("status")) else {
+            continue;
+        };
+
+        let uid = status
+            .lines()
+            .find_map(|l| l.strip_prefix("Uid:"))
+            .and_then(|l| l.split_whitespace().next())
+            .and_then(|uid| uid.parse().ok());
+        let user = uid.map_or_else(String::new, |uid| {
+            users
+                .entry(uid)
+                .or_insert_with(|| {
+                    User::from_uid(Uid::from_raw(uid))
+                        .ok()
+                        .flatten()
+                        .map_or_else(|| uid.to_string(), |u| u.name)
+                })
+                .clone()
+        });
+
+        let cmdline = std::fs::read(entry.path().join("cmdline")).unwrap_or_default();
+        let command = if cmdline.is_empty() {
+            // Kernel threads have no command line, show their name like ps does
+            let name = status
+                .lines()
+                .find_map(|l| l.strip_prefix("Name:"))
+                .unwrap_or_default();
+            format!("[{}]", name.trim())
+        } else {
+            cmdline
+                .split(|&b| b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(String::from_utf8_lossy)
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        processes.push(ProcessInfo { pid, user, command });
+    }
+
+    processes.sort_by_key(|p| p.pid);
+    Ok(processes)
+}
+
+/// State of the attach window
+#[derive(Debug, Default)]
+pub struct AttachPicker {
+    processes: Vec<ProcessInfo>,
+    filter: String,
+    selected: Option<u32>,
+    error: Option<String>,
+}
+
+impl AttachPicker {
+    /// Read the process list again
+    pub fn refresh(&mut self) {
+        match list_processes() {
+            Ok(processes) => {
+                self.processes = processes;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("Failed to list processes: {e}")),
+        }
+    }
+
+    /// Render the window contents, returning the pid to attach to
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<u32> {
+        let mut attach = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Filter");
+            ui.add(TextEdit::singleline(&mut self.filter).hint_text("name, user or pid"));
+            if ui.button("Refresh").clicked() {
+                self.refresh();
+            }
+            if ui
+                .add_enabled(self.selected.is_some(), egui::Button::new("Attach"))
+                .clicked()
+            {
+                attach = self.selected;
+            }
+        });
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+
+        ui.separator();
+
+        ScrollArea::both()
+            .auto_shrink(false)
+            .max_height(400.0)
+            .show(ui, |ui| {
+                Grid::new("attach_processes")
+                    .striped(true)
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for header in ["Pid", "User", "Command"] {
+                            ui.label(RichText::new(header).strong());
+                        }
+                        ui.end_row();
+
+                        let filter = self.filter.trim().to_lowercase();
+                        for process in self.processes.iter().filter(|p| p.matches(&filter)) {
+                            let selected = self.selected == Some(process.pid);
+                            let response = ui.selectable_label(
+                                selected,
+                                RichText::new(process.pid.to_string()).monospace(),
+                            );
+                            if response.clicked() {
+                                self.selected = Some(process.pid);
+                            }
+                            if response.double_clicked() {
+                                attach = Some(process.pid);
+                            }
+                            ui.label(&process.user);
+                            ui.monospace(&process.command);
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        attach
+    }
+}
@@ -0,0 +1,320 @@
+use crate::mi::{Tuple, parse_address, quote};
+use crate::source::GutterMarker;
+use egui::{Grid, RichText, ScrollArea, TextEdit, Ui};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A breakpoint as reported by GDB
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub number: String,
+    pub kind: String,
+    pub enabled: bool,
+    pub temporary: bool,
+    pub pending: bool,
+    pub location: String,
+    pub fullname: Option<String>,
+    pub line: Option<usize>,
+    pub address: Option<String>,
+    pub hits: u32,
+    pub condition: Option<String>,
+    pub ignore: u32,
+}
+
+impl Breakpoint {
+    /// Build a breakpoint from a `bkpt` tuple
+    pub fn from_tuple(bkpt: &Tuple) -> Option<Self> {
+        let number = bkpt.get_str("number")?.to_owned();
+
+        // Multi-location breakpoints keep their source position in the locations list
+        let first_location = bkpt
+            .get_list("locations")
+            .and_then(|l| l.first())
+            .and_then(|l| l.as_tuple());
+        let field = |name: &str| {
+            bkpt.get_str(name)
+                .or_else(|| first_location.and_then(|l| l.get_str(name)))
+        };
+
+        let fullname = field("fullname").map(str::to_owned);
+        let line = field("line").and_then(|l| l.parse().ok());
+        let location = bkpt
+            .get_str("original-location")
+            .map(str::to_owned)
+            .or_else(|| Some(format!("{}:{}", field("file")?, field("line")?)))
+            .or_else(|| field("what").map(str::to_owned))
+            .or_else(|| field("addr").map(str::to_owned))
+            .unwrap_or_default();
+
+        Some(Self {
+            number,
+            kind: bkpt.get_str("type").unwrap_or("breakpoint").to_owned(),
+            enabled: bkpt.get_str("enabled") == Some("y"),
+            temporary: bkpt.get_str("disp") == Some("del"),
+            pending: bkpt.get("pending").is_some(),
+            location,
+            fullname,
+            line,
+            address: field("addr").map(str::to_owned),
+            hits: bkpt
+                .get_str("times")
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0),
+            condition: bkpt.get_str("cond").map(str::to_owned),
+            ignore: bkpt
+                .get_str("ignore")
+                .and_then(|t| t.parse().ok())
+                .unwrap_or(0),
+        })
+    }
+
+    /// The gutter marker showing the state of the breakpoint
+    pub fn marker(&self) -> GutterMarker {
+        if self.pending {
+            GutterMarker::Pending
+        } else if self.enabled {
+            GutterMarker::Enabled
+        } else {
+            GutterMarker::Disabled
+        }
+    }
+
+    /// Check if the breakpoint is in the given source file
+    pub fn is_in(&self, path: &Path) -> bool {
+        self.fullname
+            .as_deref()
+            .is_some_and(|fullname| Path::new(fullname) == path)
+    }
+}
+
+/// Inline editor for the condition and ignore count of a breakpoint
+#[derive(Debug, Clone)]
+struct BreakpointEdit {
+    number: String,
+    condition: String,
+    ignore: String,
+}
+
+impl BreakpointEdit {
+    /// The entered ignore count, `None` when it is not a number, empty meaning none
+    fn ignore_count(&self) -> Option<u32> {
+        let ignore = self.ignore.trim();
+        if ignore.is_empty() {
+            Some(0)
+        } else {
+            ignore.parse().ok()
+        }
+    }
+}
+
+/// State of the Breakpoints tab
+#[derive(Debug, Default)]
+pub struct Breakpoints {
+    list: Vec<Breakpoint>,
+    new_location: String,
+    edit: Option<BreakpointEdit>,
+}
+
+impl Breakpoints {
+    /// Replace all breakpoints with the result of `-break-list`
+    pub fn set_from_table(&mut self, table: &Tuple) {
+        self.list = table
+            .get_list("body")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|b| b.as_tuple().and_then(Breakpoint::from_tuple))
+            .collect();
+    }
+
+    /// Add or update a breakpoint from a `bkpt` tuple
+    pub fn upsert(&mut self, bkpt: &Tuple) {
+        let Some(breakpoint) = Breakpoint::from_tuple(bkpt) else {
+            return;
+        };
+
+        match self.list.iter_mut().find(|b| b.number == breakpoint.number) {
+            Some(existing) => *existing = breakpoint,
+            None => self.list.push(breakpoint),
+        }
+    }
+
+    /// Remove a breakpoint by number
+    pub fn remove(&mut self, number: &str) {
+        self.list.retain(|b| b.number != number);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.list.iter()
+    }
+
+    /// Breakpoints set on a line of a source file
+    pub fn at_line<'a>(
+        &'a self,
+        path: &'a Path,
+        line: usize,
+    ) -> impl Iterator<Item = &'a Breakpoint> {
+        self.list
+            .iter()
+            .filter(move |b| b.line == Some(line) && b.is_in(path))
+    }
+
+    /// Breakpoints set on an instruction address
+    pub fn at_address(&self, address: u64) -> impl Iterator<Item = &Breakpoint> {
+        self.list
+            .iter()
+            .filter(move |b| b.address.as_deref().and_then(parse_address) == Some(address))
+    }
+
+    /// Gutter markers for the breakpoints in a source file, keyed by line
+    pub fn markers_for(&self, path: &Path) -> BTreeMap<usize, GutterMarker> {
+        let mut markers = BTreeMap::new();
+        for breakpoint in self.list.iter().filter(|b| b.is_in(path)) {
+            let Some(line) = breakpoint.line else {
+                continue;
+            };
+
+            let marker = breakpoint.marker();
+            markers
+                .entry(line)
+                .and_modify(|m: &mut GutterMarker| *m = m.max(marker))
+                .or_insert(marker);
+        }
+        markers
+    }
+
+    /// Render the breakpoint table, returning MI commands for the requested actions
+    pub fn ui(&mut self, ui: &mut Ui) -> Vec<String> {
+        let mut commands = Vec::new();
+
+        ui.horizontal(|ui| {
+            ui.label("Location");
+            let response = ui.add(
+                TextEdit::singleline(&mut self.new_location)
+                    .hint_text("file.c:42, function or *address")
+                    .desired_width(ui.available_width() - 60.0),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button("Add").clicked() || submitted) && !self.new_location.trim().is_empty() {
+                commands.push(format!("-break-insert {}", quote(self.new_location.trim())));
+                self.new_location.clear();
+            }
+        });
+
+        ui.separator();
+
+        ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+            Grid::new("breakpoints")
+                .striped(true)
+                .num_columns(8)
+                .show(ui, |ui| {
+                    for header in [
+                        "Num",
+                        "Type",
+                        "On",
+                        "Location",
+                        "Hits",
+                        "Condition",
+                        "Ignore",
+                    ] {
+                        ui.label(RichText::new(header).strong());
+                    }
+                    ui.label("");
+                    ui.end_row();
+
+                    let mut close_edit = false;
+                    for breakpoint in &self.list {
+                        ui.monospace(&breakpoint.number);
+                        ui.label(&breakpoint.kind);
+
+                        let mut enabled = breakpoint.enabled;
+                        if ui.checkbox(&mut enabled, "").changed() {
+                            let command = if enabled {
+                                "-break-enable"
+                            } else {
+                                "-break-disable"
+                            };
+                            commands.push(format!("{command} {}", breakpoint.number));
+                        }
+
+                        let mut location = RichText::new(&breakpoint.location).monospace();
+                        if breakpoint.pending {
+                            location = location.italics().weak();
+                        }
+                        let response = ui.label(location);
+                        if let Some(address) = &breakpoint.address {
+                            response.on_hover_text(address);
+                        }
+
+                        ui.label(breakpoint.hits.to_string());
+
+                        match &mut self.edit {
+                            Some(edit) if edit.number == breakpoint.number => {
+                                ui.add(
+                                    TextEdit::singleline(&mut edit.condition)
+                                        .hint_text("condition")
+                                        .desired_width(120.0),
+                                );
+                                let ignore = edit.ignore_count();
+                                let mut ignore_edit =
+                                    TextEdit::singleline(&mut edit.ignore).desired_width(40.0);
+                                if ignore.is_none() {
+                                    ignore_edit =
+                                        ignore_edit.text_color(ui.visuals().error_fg_color);
+                                }
+                                let response = ui.add(ignore_edit);
+                                if ignore.is_none() {
+                                    response.on_hover_text("Not a count");
+                                }
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .add_enabled(ignore.is_some(), egui::Button::new("Apply"))
+                                        .clicked()
+                                        && let Some(ignore) = ignore
+                                    {
+                                        commands.push(format!(
+                                            "-break-condition {} {}",
+                                            edit.number,
+                                            edit.condition.trim()
+                                        ));
+                                        commands
+                                            .push(format!("-break-after {} {ignore}", edit.number));
+                                        close_edit = true;
+                                    } else if ui.button("Cancel").clicked() {
+                                        close_edit = true;
+                                    }
+                                });
+                            }
+                            _ => {
+                                ui.monospace(breakpoint.condition.as_deref().unwrap_or(""));
+                                ui.label(breakpoint.ignore.to_string());
+                                ui.horizontal(|ui| {
+                                    if ui.small_button("Edit").clicked() {
+                                        self.edit = Some(BreakpointEdit {
+                                            number: breakpoint.number.clone(),
+                                            condition: breakpoint
+                                                .condition
+                                                .clone()
+                                                .unwrap_or_default(),
+                                            ignore: breakpoint.ignore.to_string(),
+                                        });
+                                    }
+                                    if ui.small_button("Delete").clicked() {
+                                        commands
+                                            .push(format!("-break-delete {}", breakpoint.number));
+                                    }
+                                });
+                            }
+                        }
+
+                        ui.end_row();
+                    }
+
+                    if close_edit {
+                        self.edit = None;
+                    }
+                });
+        });
+
+        commands
+    }
+}
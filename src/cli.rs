@@ -7,6 +7,74 @@ pub struct Cli {
     #[arg(long, default_value_t = false)]
     pub rr_replay: bool,
 
+    /// Trace directory to replay, defaults to the latest recording
+    #[arg(long, requires = "rr_replay")]
+    pub rr_trace: Option<String>,
+
+    /// Recorded pid of the process to debug in the replay
+    #[arg(long, requires = "rr_replay")]
+    pub rr_process: Option<u32>,
+
+    /// Event number to start the replay at
+    #[arg(long, requires = "rr_replay")]
+    pub rr_event: Option<u64>,
+
+    /// Attach to a running process
+    #[arg(long, value_name = "PID", conflicts_with = "rr_replay")]
+    pub attach: Option<u32>,
+
+    /// Core dump to inspect, usually together with the executable
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["rr_replay", "attach"])]
+    pub core: Option<String>,
+
+    /// Connect to a gdbserver at host:port or on a serial device
+    #[arg(
+        long,
+        value_name = "TARGET",
+        conflicts_with_all = ["rr_replay", "attach", "core"]
+    )]
+    pub remote: Option<String>,
+
+    /// Connect with target extended-remote instead of target remote
+    #[arg(long, requires = "remote")]
+    pub extended_remote: bool,
+
+    /// Where GDB finds the libraries of the remote target
+    #[arg(long, value_name = "PATH", requires = "remote")]
+    pub sysroot: Option<String>,
+
+    /// Extra directories searched for the shared libraries of the remote target
+    #[arg(long, value_name = "PATHS", requires = "remote")]
+    pub solib_search_path: Option<String>,
+
+    /// Profile of the gdbr.toml project file to start with
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = ["executable", "rr_replay", "attach", "core", "remote"]
+    )]
+    pub profile: Option<String>,
+
+    /// Arguments passed to the program when it is run
+    #[arg(long, value_name = "ARGS", allow_hyphen_values = true)]
+    pub args: Option<String>,
+
+    /// Environment variable set for the program, may be repeated
+    #[arg(long, value_name = "NAME=VALUE")]
+    pub env: Vec<String>,
+
+    /// Working directory of the program
+    #[arg(long, value_name = "DIR")]
+    pub cwd: Option<String>,
+
+    /// File the program reads its standard input from
+    #[arg(long, value_name = "PATH")]
+    pub stdin: Option<String>,
+
+    /// File the program writes its standard output to
+    #[arg(long, value_name = "PATH")]
+    pub stdout: Option<String>,
+
     /// Path to the executable
     pub executable: Option<String>,
 
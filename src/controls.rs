@@ -0,0 +1,244 @@
+use egui::{Context, Key, KeyboardShortcut, Modifiers, Ui};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Execution control commands shown in the toolbar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum ExecCommand {
+    Run,
+    Continue,
+    Pause,
+    Step,
+    Next,
+    Finish,
+    Until,
+    Restart,
+    Kill,
+    ReverseContinue,
+    ReverseStep,
+    ReverseNext,
+    ReverseFinish,
+}
+
+impl ExecCommand {
+    pub const ALL: [Self; 13] = [
+        Self::Run,
+        Self::Continue,
+        Self::Pause,
+        Self::Step,
+        Self::Next,
+        Self::Finish,
+        Self::Until,
+        Self::Restart,
+        Self::Kill,
+        Self::ReverseContinue,
+        Self::ReverseStep,
+        Self::ReverseNext,
+        Self::ReverseFinish,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Run => "Run",
+            Self::Continue => "Continue",
+            Self::Pause => "Pause",
+            Self::Step => "Step",
+            Self::Next => "Next",
+            Self::Finish => "Finish",
+            Self::Until => "Until",
+            Self::Restart => "Restart",
+            Self::Kill => "Kill",
+            Self::ReverseContinue => "Reverse continue",
+            Self::ReverseStep => "Reverse step",
+            Self::ReverseNext => "Reverse next",
+            Self::ReverseFinish => "Reverse finish",
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Run => "▶",
+            Self::Continue => "⏵",
+            Self::Pause => "⏸",
+            Self::Step => "↓",
+            Self::Next => "↷",
+            Self::Finish => "↑",
+            Self::Until => "⤓",
+            Self::Restart => "⟲",
+            Self::Kill => "⏹",
+            Self::ReverseContinue => "⏴",
+            Self::ReverseStep => "↟",
+            Self::ReverseNext => "↶",
+            Self::ReverseFinish => "⤒",
+        }
+    }
+
+    /// The MI commands sent for this command, in order
+    pub fn mi_commands(self) -> &'static [&'static str] {
+        match self {
+            Self::Run => &["-exec-run"],
+            Self::Continue => &["-exec-continue"],
+            // Interrupts the inferior through GDB, which requires `mi-async`
+            Self::Pause => &["-exec-interrupt"],
+            Self::Step => &["-exec-step"],
+            Self::Next => &["-exec-next"],
+            Self::Finish => &["-exec-finish"],
+            Self::Until => &["-exec-until"],
+            Self::Restart => &["-interpreter-exec console kill", "-exec-run"],
+            Self::Kill => &["-interpreter-exec console kill"],
+            Self::ReverseContinue => &["-exec-continue --reverse"],
+            Self::ReverseStep => &["-exec-step --reverse"],
+            Self::ReverseNext => &["-exec-next --reverse"],
+            Self::ReverseFinish => &["-exec-finish --reverse"],
+        }
+    }
+
+    /// Whether the command needs a target that can execute backwards, such as rr
+    pub fn is_reverse(self) -> bool {
+        matches!(
+            self,
+            Self::ReverseContinue | Self::ReverseStep | Self::ReverseNext | Self::ReverseFinish
+        )
+    }
+}
+
+/// Global keyboard shortcuts for the execution control commands
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Keybindings {
+    shortcuts: BTreeMap<ExecCommand, KeyboardShortcut>,
+
+    /// The command waiting for a key press to be rebound
+    #[serde(skip)]
+    capturing: Option<ExecCommand>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        let shortcut = KeyboardShortcut::new;
+        let shortcuts = [
+            (ExecCommand::Run, shortcut(Modifiers::CTRL, Key::F5)),
+            (ExecCommand::Continue, shortcut(Modifiers::NONE, Key::F5)),
+            (ExecCommand::Pause, shortcut(Modifiers::NONE, Key::F6)),
+            (ExecCommand::Step, shortcut(Modifiers::NONE, Key::F11)),
+            (ExecCommand::Next, shortcut(Modifiers::NONE, Key::F10)),
+            (ExecCommand::Finish, shortcut(Modifiers::SHIFT, Key::F11)),
+            (ExecCommand::Until, shortcut(Modifiers::NONE, Key::F7)),
+            (
+                ExecCommand::Restart,
+                shortcut(Modifiers::CTRL | Modifiers::SHIFT, Key::F5),
+            ),
+            (ExecCommand::Kill, shortcut(Modifiers::SHIFT, Key::F5)),
+            (
+                ExecCommand::ReverseContinue,
+                shortcut(Modifiers::ALT, Key::F5),
+            ),
+            (ExecCommand::ReverseStep, shortcut(Modifiers::ALT, Key::F11)),
+            (ExecCommand::ReverseNext, shortcut(Modifiers::ALT, Key::F10)),
+            (
+                ExecCommand::ReverseFinish,
+                shortcut(Modifiers::ALT | Modifiers::SHIFT, Key::F11),
+            ),
+        ];
+
+        Self {
+            shortcuts: shortcuts.into_iter().collect(),
+            capturing: None,
+        }
+    }
+}
+
+impl Keybindings {
+    /// Human readable shortcut of a command, if it has one
+    pub fn shortcut_text(&self, ctx: &Context, command: ExecCommand) -> Option<String> {
+        self.shortcuts
+            .get(&command)
+            .map(|shortcut| ctx.format_shortcut(shortcut))
+    }
+
+    /// Consume the shortcut pressed this frame, returning its command
+    pub fn pressed(&self, ctx: &Context) -> Option<ExecCommand> {
+        if self.capturing.is_some() {
+            return None;
+        }
+
+        // More specific shortcuts first, so Shift+F5 is not taken for F5
+        let mut shortcuts: Vec<_> = self.shortcuts.iter().collect();
+        shortcuts.sort_by_key(|(_, s)| {
+            let m = s.modifiers;
+            let count = [m.alt, m.ctrl, m.shift, m.mac_cmd]
+                .into_iter()
+                .filter(|&b| b);
+            std::cmp::Reverse(count.count())
+        });
+
+        ctx.input_mut(|i| {
+            shortcuts
+                .into_iter()
+                .find(|(_, shortcut)| i.consume_shortcut(shortcut))
+                .map(|(command, _)| *command)
+        })
+    }
+
+    /// Render the list of shortcuts with buttons to rebind them
+    pub fn ui(&mut self, ui: &mut Ui) {
+        if let Some(command) = self.capturing {
+            let pressed = ui.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key,
+                        pressed: true,
+                        modifiers,
+                        ..
+                    } => Some((*key, *modifiers)),
+                    _ => None,
+                })
+            });
+
+            match pressed {
+                Some((Key::Escape, _)) => self.capturing = None,
+                Some((Key::Backspace | Key::Delete, _)) => {
+                    self.shortcuts.remove(&command);
+                    self.capturing = None;
+                }
+                Some((key, modifiers)) => {
+                    let shortcut = KeyboardShortcut::new(modifiers, key);
+                    self.shortcuts.retain(|_, s| *s != shortcut);
+                    self.shortcuts.insert(command, shortcut);
+                    self.capturing = None;
+                }
+                None => {}
+            }
+        }
+
+        egui::Grid::new("keybindings")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for command in ExecCommand::ALL {
+                    ui.label(command.label());
+
+                    let text = if self.capturing == Some(command) {
+                        "Press a key...".to_owned()
+                    } else {
+                        self.shortcut_text(ui.ctx(), command)
+                            .unwrap_or_else(|| "None".to_owned())
+                    };
+                    if ui
+                        .button(text)
+                        .on_hover_text("Click to rebind, Escape to cancel, Delete to unbind")
+                        .clicked()
+                    {
+                        self.capturing = Some(command);
+                    }
+                    ui.end_row();
+                }
+            });
+
+        ui.separator();
+
+        if ui.button("Reset to defaults").clicked() {
+            *self = Self::default();
+        }
+    }
+}
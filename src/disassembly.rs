@@ -0,0 +1,201 @@
+use crate::breakpoints::Breakpoints;
+use crate::mi::{Tuple, Value, parse_address};
+use crate::source::GutterMarker;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Number of bytes disassembled around an address that is not in a known function
+pub const FALLBACK_RANGE: u64 = 256;
+
+/// A machine instruction as reported by `-data-disassemble`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub address: u64,
+    pub function: Option<String>,
+    pub offset: Option<usize>,
+    pub text: String,
+}
+
+impl Instruction {
+    /// Build an instruction from an element of `asm_insns` or `line_asm_insn`
+    pub fn from_tuple(insn: &Tuple) -> Option<Self> {
+        Some(Self {
+            address: parse_address(insn.get_str("address")?)?,
+            function: insn.get_str("func-name").map(str::to_owned),
+            offset: insn.get_str("offset").and_then(|o| o.parse().ok()),
+            text: insn.get_str("inst").unwrap_or_default().to_owned(),
+        })
+    }
+}
+
+/// A row of the Disassembly tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisassemblyLine {
+    /// A source line the following instructions were generated from
+    Source {
+        fullname: Option<String>,
+        line: usize,
+        text: String,
+    },
+    Instruction(Instruction),
+}
+
+/// State of the Disassembly tab
+#[derive(Debug, Default)]
+pub struct Disassembly {
+    lines: Vec<DisassemblyLine>,
+    pc: Option<u64>,
+    scroll_to_pc: bool,
+    error: Option<String>,
+    /// Source files read for interleaving, split into lines
+    sources: HashMap<String, Vec<String>>,
+}
+
+impl Disassembly {
+    /// Move the program counter, returning whether its instruction is already loaded
+    pub fn set_pc(&mut self, pc: u64) -> bool {
+        if self.pc != Some(pc) {
+            self.pc = Some(pc);
+            self.scroll_to_pc = true;
+        }
+        self.pc_row().is_some()
+    }
+
+    /// Replace the rows with the `asm_insns` of `-data-disassemble` in mixed mode
+    pub fn set_from_result(&mut self, insns: &[Value]) {
+        self.lines.clear();
+        self.error = None;
+        self.scroll_to_pc = true;
+
+        for item in insns.iter().filter_map(Value::as_tuple) {
+            // Without line information GDB reports bare instructions
+            let Some(line) = item.get_str("line").and_then(|l| l.parse().ok()) else {
+                self.lines
+                    .extend(Instruction::from_tuple(item).map(DisassemblyLine::Instruction));
+                continue;
+            };
+
+            let fullname = item.get_str("fullname").map(str::to_owned);
+            let text = fullname
+                .as_deref()
+                .and_then(|f| self.source_line(f, line))
+                .unwrap_or_default();
+            self.lines.push(DisassemblyLine::Source {
+                fullname,
+                line,
+                text,
+            });
+
+            self.lines.extend(
+                item.get_list("line_asm_insn")
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|i| i.as_tuple().and_then(Instruction::from_tuple))
+                    .map(DisassemblyLine::Instruction),
+            );
+        }
+    }
+
+    /// The text of a 1-based line of a source file, reading the file once
+    fn source_line(&mut self, fullname: &str, line: usize) -> Option<String> {
+        let lines = self.sources.entry(fullname.to_owned()).or_insert_with(|| {
+            std::fs::read_to_string(fullname)
+                .map(|code| code.lines().map(str::to_owned).collect())
+                .unwrap_or_default()
+        });
+        lines.get(line.checked_sub(1)?).cloned()
+    }
+
+    /// Show why the disassembly could not be loaded
+    pub fn fetch_failed(&mut self, message: &str) {
+        self.lines.clear();
+        self.error = Some(message.to_owned());
+    }
+
+    /// Forget everything, e.g. when a new GDB process is started
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// A row by its 1-based number
+    pub fn line(&self, row: usize) -> Option<&DisassemblyLine> {
+        self.lines.get(row.checked_sub(1)?)
+    }
+
+    /// The text of all rows, one per line
+    pub fn code(&self) -> String {
+        let mut code = String::new();
+        for line in &self.lines {
+            match line {
+                DisassemblyLine::Source { text, .. } => code.push_str(text),
+                DisassemblyLine::Instruction(insn) => {
+                    let symbol = match (&insn.function, insn.offset) {
+                        (Some(function), Some(offset)) => format!("<{function}+{offset}>"),
+                        (Some(function), None) => format!("<{function}>"),
+                        _ => String::new(),
+                    };
+                    code.push_str(&format!("    {symbol:<24} {}", insn.text));
+                }
+            }
+            code.push('\n');
+        }
+        code
+    }
+
+    /// The gutter label of a 1-based row, the address of instructions or the source line number
+    pub fn label(&self, row: usize) -> String {
+        match self.line(row) {
+            Some(DisassemblyLine::Instruction(insn)) => format!("0x{:016x} ", insn.address),
+            Some(DisassemblyLine::Source { line, .. }) => format!("{line:>18} "),
+            None => String::new(),
+        }
+    }
+
+    /// The 1-based row of the instruction at the program counter
+    pub fn pc_row(&self) -> Option<usize> {
+        let pc = self.pc?;
+        self.lines
+            .iter()
+            .position(|l| matches!(l, DisassemblyLine::Instruction(insn) if insn.address == pc))
+            .map(|i| i + 1)
+    }
+
+    /// Check if the view should scroll to the program counter and reset the flag
+    pub fn take_scroll_to_pc(&mut self) -> bool {
+        std::mem::take(&mut self.scroll_to_pc)
+    }
+
+    /// Gutter markers for the breakpoints on the rows, keyed by 1-based row
+    pub fn markers(&self, breakpoints: &Breakpoints) -> BTreeMap<usize, GutterMarker> {
+        let mut markers = BTreeMap::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            let marker = match line {
+                DisassemblyLine::Instruction(insn) => breakpoints
+                    .at_address(insn.address)
+                    .map(|b| b.marker())
+                    .reduce(GutterMarker::max),
+                DisassemblyLine::Source {
+                    fullname: Some(fullname),
+                    line,
+                    ..
+                } => breakpoints
+                    .at_line(Path::new(fullname), *line)
+                    .map(|b| b.marker())
+                    .reduce(GutterMarker::max),
+                DisassemblyLine::Source { fullname: None, .. } => None,
+            };
+            if let Some(marker) = marker {
+                markers.insert(i + 1, marker);
+            }
+        }
+        markers
+    }
+}
@@ -1,22 +1,116 @@
+use crate::mi::{Record, ResultRecord};
 use nix::sys::signal::{self, Signal};
+use std::collections::HashMap;
 use std::error::Error;
+use std::fmt;
 use std::io::{BufRead as _, BufReader, Write as _};
 use std::process::{Child, Command, Stdio};
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default time to wait for the result of a [`Request`]
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Senders for the result records of in-flight requests, keyed by token
+type PendingRequests = Arc<Mutex<HashMap<u64, Sender<ResultRecord>>>>;
+
+/// A line of output read from the GDB process
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GdbOutput {
+    /// A record GDB wrote to its standard output
+    Mi(Record),
+    /// A line GDB wrote to its standard error
+    Stderr(String),
+}
 
 /// A wrapper around a GDB process
 #[derive(Debug)]
 pub struct Gdb {
     process: Child,
     command_sender: Sender<String>,
-    output_receiver: Receiver<String>,
+    output_receiver: Receiver<GdbOutput>,
+    next_token: AtomicU64,
+    pending_requests: PendingRequests,
+}
+
+/// Why a [`Request`] did not produce a result record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestError {
+    /// No result arrived before the deadline
+    Timeout,
+    /// The GDB process went away
+    Disconnected,
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout => write!(f, "Timed out waiting for GDB"),
+            Self::Disconnected => write!(f, "GDB disconnected"),
+        }
+    }
+}
+
+impl Error for RequestError {}
+
+/// A command sent with [`Gdb::request`], resolved with the matching result record
+#[derive(Debug)]
+pub struct Request {
+    token: u64,
+    receiver: Receiver<ResultRecord>,
+    deadline: Instant,
+    pending_requests: PendingRequests,
+}
+
+impl Request {
+    /// The MI token the command was tagged with
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Check for the result without blocking, `Ok(None)` means it is still pending
+    pub fn try_result(&self) -> Result<Option<ResultRecord>, RequestError> {
+        match self.receiver.try_recv() {
+            Ok(record) => Ok(Some(record)),
+            Err(TryRecvError::Disconnected) => Err(RequestError::Disconnected),
+            Err(TryRecvError::Empty) if Instant::now() >= self.deadline => {
+                Err(RequestError::Timeout)
+            }
+            Err(TryRecvError::Empty) => Ok(None),
+        }
+    }
+
+    /// Block until the result arrives or the request times out
+    pub fn wait(&self) -> Result<ResultRecord, RequestError> {
+        let timeout = self.deadline.saturating_duration_since(Instant::now());
+        self.receiver.recv_timeout(timeout).map_err(|e| match e {
+            RecvTimeoutError::Timeout => RequestError::Timeout,
+            RecvTimeoutError::Disconnected => RequestError::Disconnected,
+        })
+    }
+}
+
+impl Drop for Request {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending_requests.lock() {
+            pending.remove(&self.token);
+        }
+    }
 }
 
 impl Gdb {
-    /// Spawn a new GDB process
-    pub fn new(args: Vec<String>) -> Result<Self, Box<dyn Error>> {
+    /// Spawn a new GDB process speaking the GDB/MI machine interface
+    ///
+    /// The context is repainted whenever GDB writes output, so it is handled right away.
+    pub fn new(args: Vec<String>, ctx: &egui::Context) -> Result<Self, Box<dyn Error>> {
         let mut process = Command::new("gdb")
+            .arg("--interpreter=mi3")
+            // Lets `-exec-interrupt` pause the inferior while GDB keeps accepting commands.
+            // Set before any `-ex` connects to a target, GDB refuses to change it afterwards.
+            .args(["-iex", "set mi-async on"])
             .args(args)
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
@@ -29,6 +123,7 @@ impl Gdb {
         // Spawn a thread to handle command sending
         let stdin = process.stdin.take().ok_or("Failed to get stdin")?;
         let stdout = process.stdout.take().ok_or("Failed to get stdout")?;
+        let stderr = process.stderr.take().ok_or("Failed to get stderr")?;
 
         thread::spawn(move || {
             let mut stdin = stdin;
@@ -44,14 +139,58 @@ impl Gdb {
             }
         });
 
+        // Drain stderr so GDB never blocks on a full pipe, merging it into the output
+        let stderr_sender = output_sender.clone();
+        let stderr_ctx = ctx.clone();
+        thread::spawn(move || {
+            let reader = BufReader::new(stderr);
+            for line in reader.lines() {
+                match line {
+                    Ok(line) => {
+                        if stderr_sender.send(GdbOutput::Stderr(line)).is_err() {
+                            break; // Receiver was dropped
+                        }
+                        stderr_ctx.request_repaint();
+                    }
+                    Err(e) => {
+                        eprintln!("Error reading from GDB stderr: {e}");
+                        break;
+                    }
+                }
+            }
+        });
+
+        let pending_requests = PendingRequests::default();
+        let pending = Arc::clone(&pending_requests);
+        let ctx = ctx.clone();
+
         thread::spawn(move || {
             let reader = BufReader::new(stdout);
             for line in reader.lines() {
                 match line {
                     Ok(line) => {
-                        if output_sender.send(line).is_err() {
+                        let record = match Record::parse(&line) {
+                            Record::Result(result) => {
+                                // Route results of tracked requests to their handle
+                                let sender = result.token.and_then(|token| {
+                                    pending.lock().ok().and_then(|mut p| p.remove(&token))
+                                });
+                                match sender {
+                                    Some(sender) => {
+                                        let _ = sender.send(result);
+                                        ctx.request_repaint();
+                                        continue;
+                                    }
+                                    None => Record::Result(result),
+                                }
+                            }
+                            record => record,
+                        };
+
+                        if output_sender.send(GdbOutput::Mi(record)).is_err() {
                             break; // Receiver was dropped
                         }
+                        ctx.request_repaint();
                     }
                     Err(e) => {
                         eprintln!("Error reading from GDB stdout: {e}");
@@ -65,6 +204,8 @@ impl Gdb {
             process,
             command_sender,
             output_receiver,
+            next_token: AtomicU64::new(1),
+            pending_requests,
         })
     }
 
@@ -74,13 +215,44 @@ impl Gdb {
         Ok(())
     }
 
+    /// Send an MI command tagged with a token, returning a handle for its result
+    pub fn request(&self, command: &str) -> Result<Request, Box<dyn Error>> {
+        self.request_with_timeout(command, DEFAULT_REQUEST_TIMEOUT)
+    }
+
+    /// Like [`Self::request`], with a custom timeout
+    pub fn request_with_timeout(
+        &self,
+        command: &str,
+        timeout: Duration,
+    ) -> Result<Request, Box<dyn Error>> {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel();
+
+        self.pending_requests
+            .lock()
+            .map_err(|e| e.to_string())?
+            .insert(token, sender);
+
+        let request = Request {
+            token,
+            receiver,
+            deadline: Instant::now() + timeout,
+            pending_requests: Arc::clone(&self.pending_requests),
+        };
+
+        self.send_command(format!("{token}{command}"))?;
+
+        Ok(request)
+    }
+
     /// Try to receive output from GDB (non-blocking)
-    pub fn try_receive_output(&self) -> Option<String> {
+    pub fn try_receive_output(&self) -> Option<GdbOutput> {
         self.output_receiver.try_recv().ok()
     }
 
     /// Receive output from GDB (blocking)
-    pub fn receive_output(&self) -> Result<String, Box<dyn Error>> {
+    pub fn receive_output(&self) -> Result<GdbOutput, Box<dyn Error>> {
         Ok(self.output_receiver.recv()?)
     }
 
@@ -121,7 +293,7 @@ impl Gdb {
 
 impl Drop for Gdb {
     fn drop(&mut self) {
-        let _ = self.send_command("quit".into());
+        let _ = self.send_command("-gdb-exit".into());
 
         std::thread::sleep(std::time::Duration::from_millis(100));
 
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+
+/// Commands kept in the history, older ones are dropped
+const MAX_ENTRIES: usize = 1000;
+
+/// A reverse search started with Ctrl-R
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Search {
+    query: String,
+    /// Index of the entry matching the query
+    found: Option<usize>,
+}
+
+/// Commands entered in the Console, persisted across sessions
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct History {
+    /// Oldest first, without duplicates
+    entries: Vec<String>,
+    /// Index of the entry recalled with Up and Down
+    #[serde(skip)]
+    position: Option<usize>,
+    /// The input before browsing started, restored when going past the newest entry
+    #[serde(skip)]
+    draft: String,
+    #[serde(skip)]
+    search: Option<Search>,
+}
+
+impl History {
+    /// Add a command, moving it to the end if it was entered before
+    pub fn push(&mut self, command: &str) {
+        self.position = None;
+        self.search = None;
+
+        let command = command.trim();
+        if command.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != command);
+        self.entries.push(command.to_owned());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    /// The newest command, repeated when Enter is pressed on an empty input
+    pub fn last(&self) -> Option<&str> {
+        self.entries.last().map(String::as_str)
+    }
+
+    /// Replace the input with the previous, older command
+    pub fn previous(&mut self, input: &mut String) {
+        let index = match self.position {
+            None if self.entries.is_empty() => return,
+            None => {
+                self.draft.clone_from(input);
+                self.entries.len() - 1
+            }
+            Some(0) => return,
+            Some(position) => position - 1,
+        };
+        self.position = Some(index);
+        input.clone_from(&self.entries[index]);
+    }
+
+    /// Replace the input with the next, newer command, or what was typed before browsing
+    pub fn next(&mut self, input: &mut String) {
+        let Some(position) = self.position else {
+            return;
+        };
+        if position + 1 < self.entries.len() {
+            self.position = Some(position + 1);
+            input.clone_from(&self.entries[position + 1]);
+        } else {
+            self.position = None;
+            *input = std::mem::take(&mut self.draft);
+        }
+    }
+
+    pub fn is_searching(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// Start a reverse search, or look for an older match when already searching
+    pub fn search_older(&mut self) {
+        let Some(search) = &mut self.search else {
+            self.search = Some(Search::default());
+            return;
+        };
+        let before = search.found.unwrap_or(self.entries.len());
+        if let Some(found) = find(&self.entries, &search.query, before) {
+            search.found = Some(found);
+        }
+    }
+
+    /// The query typed while searching
+    pub fn search_query_mut(&mut self) -> Option<&mut String> {
+        self.search.as_mut().map(|search| &mut search.query)
+    }
+
+    /// Find the newest match again after the query changed
+    pub fn update_search(&mut self) {
+        if let Some(search) = &mut self.search {
+            search.found = find(&self.entries, &search.query, self.entries.len());
+        }
+    }
+
+    /// The command matching the search query
+    pub fn search_match(&self) -> Option<&str> {
+        let found = self.search.as_ref()?.found?;
+        self.entries.get(found).map(String::as_str)
+    }
+
+    /// Stop searching, returning the matching command
+    pub fn finish_search(&mut self) -> Option<String> {
+        let command = self.search_match().map(str::to_owned);
+        self.search = None;
+        command
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.search = None;
+    }
+}
+
+/// The newest entry before `before` containing the query
+fn find(entries: &[String], query: &str, before: usize) -> Option<usize> {
+    if query.is_empty() {
+        return None;
+    }
+    entries[..before.min(entries.len())]
+        .iter()
+        .rposition(|entry| entry.contains(query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(entries: &[&str]) -> History {
+        let mut history = History::default();
+        for entry in entries {
+            history.push(entry);
+        }
+        history
+    }
+
+    #[test]
+    fn push_trims_skips_blank_and_dedups() {
+        let history = history(&["a", "  ", "b ", "a", " b"]);
+        assert_eq!(history.entries, vec!["a".to_owned(), "b".to_owned()]);
+        assert_eq!(history.last(), Some("b"));
+    }
+
+    #[test]
+    fn push_drops_oldest() {
+        let mut history = History::default();
+        for i in 0..=MAX_ENTRIES {
+            history.push(&i.to_string());
+        }
+        assert_eq!(history.entries.len(), MAX_ENTRIES);
+        assert_eq!(history.entries.first().map(String::as_str), Some("1"));
+    }
+
+    #[test]
+    fn browse_restores_draft() {
+        let mut history = history(&["first", "second"]);
+        let mut input = "draft".to_owned();
+
+        history.previous(&mut input);
+        assert_eq!(input, "second");
+        history.previous(&mut input);
+        assert_eq!(input, "first");
+        history.previous(&mut input);
+        assert_eq!(input, "first");
+
+        history.next(&mut input);
+        assert_eq!(input, "second");
+        history.next(&mut input);
+        assert_eq!(input, "draft");
+        history.next(&mut input);
+        assert_eq!(input, "draft");
+    }
+
+    #[test]
+    fn browse_empty_history() {
+        let mut history = History::default();
+        let mut input = "typed".to_owned();
+        history.previous(&mut input);
+        history.next(&mut input);
+        assert_eq!(input, "typed");
+    }
+
+    #[test]
+    fn reverse_search() {
+        let mut history = history(&["break main", "run", "break foo.c:3", "next"]);
+        history.search_older();
+        assert!(history.is_searching());
+        assert_eq!(history.search_match(), None);
+
+        if let Some(query) = history.search_query_mut() {
+            query.push_str("break");
+        }
+        history.update_search();
+        assert_eq!(history.search_match(), Some("break foo.c:3"));
+
+        history.search_older();
+        assert_eq!(history.search_match(), Some("break main"));
+        // No older match keeps the current one
+        history.search_older();
+        assert_eq!(history.search_match(), Some("break main"));
+
+        assert_eq!(history.finish_search(), Some("break main".to_owned()));
+        assert!(!history.is_searching());
+    }
+
+    #[test]
+    fn cancel_search() {
+        let mut history = history(&["run"]);
+        history.search_older();
+        history.cancel_search();
+        assert!(!history.is_searching());
+        assert_eq!(history.search_query_mut(), None);
+        assert_eq!(history.finish_search(), None);
+    }
+
+    #[test]
+    fn only_entries_are_persisted() {
+        let mut history = history(&["run"]);
+        let mut input = String::new();
+        history.previous(&mut input);
+        history.search_older();
+
+        let restored: History =
+            toml::from_str(&toml::to_string(&history).unwrap_or_default()).unwrap_or_default();
+        assert_eq!(restored.entries, vec!["run".to_owned()]);
+        assert_eq!(restored.position, None);
+        assert!(!restored.is_searching());
+    }
+}
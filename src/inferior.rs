@@ -0,0 +1,258 @@
+use crate::controls::ExecCommand;
+use crate::mi::Tuple;
+use egui::{Color32, Ui};
+use nix::sys::signal::Signal;
+
+/// Why the inferior stopped, from the `reason` of a `*stopped` record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StopReason {
+    Breakpoint {
+        number: String,
+    },
+    Watchpoint {
+        number: String,
+        expression: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    Signal {
+        name: String,
+        meaning: Option<String>,
+    },
+    EndSteppingRange,
+    FunctionFinished {
+        value: Option<String>,
+    },
+    LocationReached,
+    Other(String),
+    /// Stopped without a reason, e.g. right after attaching
+    Unknown,
+}
+
+impl StopReason {
+    pub fn from_stopped(stopped: &Tuple) -> Self {
+        let field = |name: &str| stopped.get_str(name).map(str::to_owned);
+
+        match stopped.get_str("reason") {
+            Some("breakpoint-hit") => Self::Breakpoint {
+                number: field("bkptno").unwrap_or_default(),
+            },
+            Some(
+                "watchpoint-trigger" | "read-watchpoint-trigger" | "access-watchpoint-trigger",
+            ) => {
+                let watchpoint = ["wpt", "hw-rwpt", "hw-awpt"]
+                    .into_iter()
+                    .find_map(|name| stopped.get_tuple(name));
+                let value = stopped.get_tuple("value");
+                Self::Watchpoint {
+                    number: watchpoint
+                        .and_then(|w| w.get_str("number"))
+                        .unwrap_or_default()
+                        .to_owned(),
+                    expression: watchpoint
+                        .and_then(|w| w.get_str("exp"))
+                        .unwrap_or_default()
+                        .to_owned(),
+                    old: value.and_then(|v| v.get_str("old")).map(str::to_owned),
+                    new: value
+                        .and_then(|v| v.get_str("new").or_else(|| v.get_str("value")))
+                        .map(str::to_owned),
+                }
+            }
+            Some("signal-received") => Self::Signal {
+                name: field("signal-name").unwrap_or_default(),
+                meaning: field("signal-meaning"),
+            },
+            Some("end-stepping-range") => Self::EndSteppingRange,
+            Some("function-finished") => Self::FunctionFinished {
+                value: field("return-value"),
+            },
+            Some("location-reached") => Self::LocationReached,
+            Some(reason) => Self::Other(reason.to_owned()),
+            None => Self::Unknown,
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::Breakpoint { number } => format!("Breakpoint {number} hit"),
+            Self::Watchpoint {
+                number,
+                expression,
+                old,
+                new,
+            } => match (old, new) {
+                (Some(old), Some(new)) => {
+                    format!(
+                        "Watchpoint {number} triggered: {expression} changed from {old} to {new}"
+                    )
+                }
+                (None, Some(value)) => {
+                    format!("Watchpoint {number} triggered: {expression} = {value}")
+                }
+                _ => format!("Watchpoint {number} triggered: {expression}"),
+            },
+            Self::Signal {
+                name,
+                meaning: Some(meaning),
+            } => format!("Signal {name} received, {meaning}"),
+            Self::Signal {
+                name,
+                meaning: None,
+            } => format!("Signal {name} received"),
+            Self::EndSteppingRange => "Step finished".to_owned(),
+            Self::FunctionFinished { value: Some(value) } => {
+                format!("Function finished, returned {value}")
+            }
+            Self::FunctionFinished { value: None } => "Function finished".to_owned(),
+            Self::LocationReached => "Location reached".to_owned(),
+            Self::Other(reason) => format!("Stopped ({reason})"),
+            Self::Unknown => "Stopped".to_owned(),
+        }
+    }
+}
+
+/// Execution state of the debugged program
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum InferiorState {
+    #[default]
+    NotStarted,
+    Running,
+    Stopped {
+        reason: StopReason,
+        /// `function at file:line` of the frame the inferior stopped in
+        location: Option<String>,
+    },
+    /// The exit code, `None` when it was killed or terminated by a signal
+    Exited(Option<i32>),
+    /// Inspecting a core dump, which cannot be executed
+    PostMortem {
+        /// The signal that terminated the process
+        signal: Option<String>,
+        /// `function at file:line` of the frame the process crashed in
+        location: Option<String>,
+    },
+}
+
+impl InferiorState {
+    /// The state after a `*stopped` record, which also reports the inferior exiting
+    pub fn from_stopped(stopped: &Tuple) -> Self {
+        match stopped.get_str("reason") {
+            Some("exited-normally") => Self::Exited(Some(0)),
+            Some("exited") => Self::Exited(stopped.get_str("exit-code").and_then(parse_exit_code)),
+            Some("exited-signalled") => Self::Exited(None),
+            _ => Self::Stopped {
+                reason: StopReason::from_stopped(stopped),
+                location: stopped.get_tuple("frame").map(frame_location),
+            },
+        }
+    }
+
+    /// The state of a core dump before GDB reported where it crashed
+    pub fn post_mortem() -> Self {
+        Self::PostMortem {
+            signal: None,
+            location: None,
+        }
+    }
+
+    /// Record the frame the inferior is stopped or crashed in
+    pub fn set_frame(&mut self, frame: &Tuple) {
+        match self {
+            Self::Stopped { location, .. } | Self::PostMortem { location, .. } => {
+                *location = Some(frame_location(frame));
+            }
+            // A remote target is already stopped when GDB connects to it
+            Self::NotStarted => {
+                *self = Self::Stopped {
+                    reason: StopReason::Unknown,
+                    location: Some(frame_location(frame)),
+                };
+            }
+            Self::Running | Self::Exited(_) => {}
+        }
+    }
+
+    /// Record the value of `$_exitsignal`, the signal number that terminated a core dump
+    pub fn set_crash_signal(&mut self, value: &str) {
+        if let Self::PostMortem { signal, .. } = self {
+            *signal = value
+                .parse::<i32>()
+                .ok()
+                .and_then(|number| Signal::try_from(number).ok())
+                .map(|s| s.as_str().to_owned());
+        }
+    }
+
+    /// The state after a `=thread-group-exited` record
+    pub fn from_thread_group_exited(exited: &Tuple) -> Self {
+        Self::Exited(exited.get_str("exit-code").and_then(parse_exit_code))
+    }
+
+    pub fn is_stopped(&self) -> bool {
+        matches!(self, Self::Stopped { .. })
+    }
+
+    /// Whether an execution control command makes sense in this state
+    pub fn allows(&self, command: ExecCommand) -> bool {
+        match self {
+            Self::PostMortem { .. } => false,
+            Self::NotStarted | Self::Exited(_) => command == ExecCommand::Run,
+            Self::Running => matches!(
+                command,
+                ExecCommand::Pause | ExecCommand::Restart | ExecCommand::Kill
+            ),
+            Self::Stopped { .. } => !matches!(command, ExecCommand::Run | ExecCommand::Pause),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            Self::NotStarted => "Not started".to_owned(),
+            Self::Running => "Running".to_owned(),
+            Self::Stopped {
+                reason,
+                location: Some(location),
+            } => format!("{} in {location}", reason.description()),
+            Self::Stopped {
+                reason,
+                location: None,
+            } => reason.description(),
+            Self::Exited(Some(code)) => format!("Exited with code {code}"),
+            Self::Exited(None) => "Exited".to_owned(),
+            Self::PostMortem { signal, location } => match (signal, location) {
+                (Some(signal), Some(location)) => {
+                    format!("Post-mortem: {signal} in {location}")
+                }
+                (Some(signal), None) => format!("Post-mortem: {signal}"),
+                (None, Some(location)) => format!("Post-mortem: crashed in {location}"),
+                (None, None) => "Post-mortem".to_owned(),
+            },
+        }
+    }
+
+    /// Render the state for the status bar
+    pub fn ui(&self, ui: &mut Ui) {
+        let color = match self {
+            Self::NotStarted | Self::Exited(_) => ui.visuals().weak_text_color(),
+            Self::Running => Color32::from_rgb(80, 200, 80),
+            Self::Stopped { .. } => ui.visuals().warn_fg_color,
+            Self::PostMortem { .. } => ui.visuals().error_fg_color,
+        };
+        ui.colored_label(color, self.description());
+    }
+}
+
+/// Describe a frame as `function at file:line`
+fn frame_location(frame: &Tuple) -> String {
+    let function = frame.get_str("func").unwrap_or("??");
+    match (frame.get_str("file"), frame.get_str("line")) {
+        (Some(file), Some(line)) => format!("{function} at {file}:{line}"),
+        _ => function.to_owned(),
+    }
+}
+
+/// Parse an exit code, which GDB prints in octal
+fn parse_exit_code(code: &str) -> Option<i32> {
+    i32::from_str_radix(code, 8).ok()
+}
@@ -0,0 +1,308 @@
+use crate::cli::Cli;
+use crate::mi::quote;
+use egui::{Grid, TextEdit, TextStyle, Ui};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// An environment variable set for the inferior
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EnvVar {
+    pub name: String,
+    pub value: String,
+}
+
+/// How the inferior is started by Run
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    /// Program arguments, split by the shell GDB starts the inferior with
+    pub args: String,
+    pub env: Vec<EnvVar>,
+    /// Working directory of the program, GDB's own when empty
+    pub cwd: String,
+    /// File the standard input is read from, unredirected when empty
+    pub stdin: String,
+    /// File the standard output is written to, unredirected when empty
+    pub stdout: String,
+}
+
+impl LaunchConfig {
+    /// Override the settings given on the command line
+    pub fn apply_cli(&mut self, cli: &Cli) {
+        if let Some(args) = &cli.args {
+            self.args.clone_from(args);
+        }
+        for var in &cli.env {
+            let (name, value) = var.split_once('=').unwrap_or((var, ""));
+            match self.env.iter_mut().find(|v| v.name == name) {
+                Some(existing) => value.clone_into(&mut existing.value),
+                None => self.env.push(EnvVar {
+                    name: name.to_owned(),
+                    value: value.to_owned(),
+                }),
+            }
+        }
+        if let Some(cwd) = &cli.cwd {
+            self.cwd.clone_from(cwd);
+        }
+        if let Some(stdin) = &cli.stdin {
+            self.stdin.clone_from(stdin);
+        }
+        if let Some(stdout) = &cli.stdout {
+            self.stdout.clone_from(stdout);
+        }
+    }
+
+    /// Names of the environment variables set for the inferior
+    pub fn env_names(&self) -> Vec<String> {
+        self.env
+            .iter()
+            .map(|v| v.name.trim())
+            .filter(|name| !name.is_empty())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Commands applying the configuration before the inferior is run
+    ///
+    /// Variables set by an earlier run but since removed are unset, GDB keeps them otherwise.
+    pub fn commands(&self, previous_env: &[String]) -> Vec<String> {
+        // The redirections are handled by the shell, like the arguments
+        let mut args = self.args.trim().to_owned();
+        for (redirect, path) in [("<", &self.stdin), (">", &self.stdout)] {
+            if !path.trim().is_empty() {
+                args.push_str(&format!(" {redirect} {}", shell_quote(path.trim())));
+            }
+        }
+
+        // GDB passes the rest of the line to `set args` as is, and clears them when it is empty
+        let mut commands = vec![format!("-exec-arguments {}", args.trim())];
+        // The program's own directory, leaving GDB's alone, and reset to GDB's when empty
+        commands.push(format!("-gdb-set cwd {}", self.cwd.trim()));
+        for var in self.env.iter().filter(|v| !v.name.trim().is_empty()) {
+            let set = format!("set environment {}={}", var.name.trim(), var.value);
+            commands.push(format!("-interpreter-exec console {}", quote(&set)));
+        }
+        let names = self.env_names();
+        for name in previous_env.iter().filter(|name| !names.contains(name)) {
+            let unset = format!("unset environment {name}");
+            commands.push(format!("-interpreter-exec console {}", quote(&unset)));
+        }
+        commands
+    }
+
+    /// Render the launch configuration editor
+    pub fn ui(&mut self, ui: &mut Ui) {
+        Grid::new("launch_config")
+            .num_columns(2)
+            .spacing([8.0, 6.0])
+            .show(ui, |ui| {
+                ui.label("Arguments");
+                ui.add(
+                    TextEdit::singleline(&mut self.args)
+                        .font(TextStyle::Monospace)
+                        .desired_width(300.0),
+                );
+                ui.end_row();
+
+                ui.label("Working directory");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        TextEdit::singleline(&mut self.cwd)
+                            .font(TextStyle::Monospace)
+                            .hint_text("GDB's working directory"),
+                    );
+                    if ui.button("…").clicked()
+                        && let Some(dir) = rfd::FileDialog::new().pick_folder()
+                    {
+                        self.cwd = dir.display().to_string();
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Standard input");
+                ui.add(
+                    TextEdit::singleline(&mut self.stdin)
+                        .font(TextStyle::Monospace)
+                        .hint_text("file"),
+                );
+                ui.end_row();
+
+                ui.label("Standard output");
+                ui.add(
+                    TextEdit::singleline(&mut self.stdout)
+                        .font(TextStyle::Monospace)
+                        .hint_text("file"),
+                );
+                ui.end_row();
+            });
+
+        ui.separator();
+        ui.label("Environment");
+
+        let mut remove = None;
+        Grid::new("launch_env").num_columns(3).show(ui, |ui| {
+            for (index, var) in self.env.iter_mut().enumerate() {
+                ui.add(
+                    TextEdit::singleline(&mut var.name)
+                        .font(TextStyle::Monospace)
+                        .hint_text("NAME")
+                        .desired_width(120.0),
+                );
+                ui.add(
+                    TextEdit::singleline(&mut var.value)
+                        .font(TextStyle::Monospace)
+                        .hint_text("value")
+                        .desired_width(200.0),
+                );
+                if ui.small_button("Delete").clicked() {
+                    remove = Some(index);
+                }
+                ui.end_row();
+            }
+        });
+        if let Some(index) = remove {
+            self.env.remove(index);
+        }
+
+        if ui.button("Add variable").clicked() {
+            self.env.push(EnvVar::default());
+        }
+
+        ui.weak("Applied the next time the program is run");
+    }
+}
+
+/// The launch configurations of every executable, keyed by path
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LaunchConfigs {
+    configs: BTreeMap<String, LaunchConfig>,
+}
+
+impl LaunchConfigs {
+    pub fn get(&self, executable: &str) -> Option<&LaunchConfig> {
+        self.configs.get(executable)
+    }
+
+    pub fn get_mut(&mut self, executable: &str) -> &mut LaunchConfig {
+        self.configs.entry(executable.to_owned()).or_default()
+    }
+}
+
+/// Quote a word for the shell GDB runs the inferior with
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn var(name: &str, value: &str) -> EnvVar {
+        EnvVar {
+            name: name.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    #[test]
+    fn empty_config_clears_arguments() {
+        assert_eq!(
+            LaunchConfig::default().commands(&[]),
+            vec!["-exec-arguments ".to_owned(), "-gdb-set cwd ".to_owned()]
+        );
+    }
+
+    #[test]
+    fn arguments_and_redirections_are_passed_as_is() {
+        let config = LaunchConfig {
+            args: " --port 8080 \"a b\" ".to_owned(),
+            stdin: "in put.txt".to_owned(),
+            stdout: "it's.log".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.commands(&[]),
+            vec![
+                r#"-exec-arguments --port 8080 "a b" < 'in put.txt' > 'it'\''s.log'"#.to_owned(),
+                "-gdb-set cwd ".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn cwd_and_environment() {
+        let config = LaunchConfig {
+            env: vec![var("RUST_LOG", "debug"), var(" ", "ignored")],
+            cwd: "/tmp/my dir".to_owned(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.commands(&[]),
+            vec![
+                "-exec-arguments ".to_owned(),
+                "-gdb-set cwd /tmp/my dir".to_owned(),
+                r#"-interpreter-exec console "set environment RUST_LOG=debug""#.to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn clearing_cwd_resets_it() {
+        let mut config = LaunchConfig {
+            cwd: "/srv".to_owned(),
+            ..Default::default()
+        };
+        assert!(
+            config
+                .commands(&[])
+                .contains(&"-gdb-set cwd /srv".to_owned())
+        );
+        config.cwd = "  ".to_owned();
+        assert!(config.commands(&[]).contains(&"-gdb-set cwd ".to_owned()));
+        assert!(
+            !config
+                .commands(&[])
+                .iter()
+                .any(|c| c.starts_with("-environment-cd"))
+        );
+    }
+
+    #[test]
+    fn removed_variables_are_unset() {
+        let config = LaunchConfig {
+            env: vec![var("KEEP", "1")],
+            ..Default::default()
+        };
+        assert_eq!(config.env_names(), vec!["KEEP".to_owned()]);
+        assert_eq!(
+            config.commands(&["KEEP".to_owned(), "GONE".to_owned()]),
+            vec![
+                "-exec-arguments ".to_owned(),
+                "-gdb-set cwd ".to_owned(),
+                r#"-interpreter-exec console "set environment KEEP=1""#.to_owned(),
+                r#"-interpreter-exec console "unset environment GONE""#.to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn cli_overrides() {
+        let mut config = LaunchConfig {
+            args: "old".to_owned(),
+            env: vec![var("A", "1")],
+            ..Default::default()
+        };
+        let cli = Cli {
+            args: Some("new".to_owned()),
+            env: vec!["A=2".to_owned(), "B".to_owned()],
+            cwd: Some("/".to_owned()),
+            ..Default::default()
+        };
+        config.apply_cli(&cli);
+        assert_eq!(config.args, "new");
+        assert_eq!(config.env, vec![var("A", "2"), var("B", "")]);
+        assert_eq!(config.cwd, "/");
+    }
+}
@@ -1,7 +1,27 @@
 pub mod app;
+pub mod attach;
+pub mod breakpoints;
 pub mod cli;
+pub mod controls;
+pub mod disassembly;
 pub mod gdb;
+pub mod history;
+pub mod inferior;
+pub mod launch;
+pub mod memory;
+pub mod mi;
+pub mod output;
+pub mod profiles;
+pub mod registers;
+pub mod remote;
+pub mod rr;
+pub mod session;
+pub mod source;
+pub mod stack;
 pub mod tabs;
+pub mod threads;
+pub mod traces;
 pub mod ui;
+pub mod variables;
 
 pub use app::Gdbr;
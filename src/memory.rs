@@ -0,0 +1,344 @@
+use crate::mi::{Value, parse_address};
+use egui::{Grid, Key, RichText, ScrollArea, TextEdit, TextStyle, Ui};
+use std::collections::BTreeMap;
+
+/// Number of rows fetched and shown at once
+pub const ROWS: usize = 32;
+
+/// Byte order used to group bytes into words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    pub const ALL: [Self; 2] = [Self::Little, Self::Big];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Little => "Little",
+            Self::Big => "Big",
+        }
+    }
+}
+
+/// An action requested from the Data tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemoryAction {
+    /// Read `count` bytes starting at an address or expression
+    Read { address: String, count: usize },
+    /// Write bytes starting at an address
+    Write { address: u64, bytes: Vec<u8> },
+}
+
+/// State of the Data tab
+#[derive(Debug)]
+pub struct Memory {
+    address_input: String,
+    /// Address of the first shown byte, once something has been read
+    base: Option<u64>,
+    bytes: BTreeMap<u64, u8>,
+    /// Bytes as they were when the inferior last stopped
+    previous: BTreeMap<u64, u8>,
+    word_size: usize,
+    endianness: Endianness,
+    bytes_per_row: usize,
+    error: Option<String>,
+    edit: Option<(u64, String)>,
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self {
+            address_input: String::new(),
+            base: None,
+            bytes: BTreeMap::new(),
+            previous: BTreeMap::new(),
+            word_size: 1,
+            endianness: Endianness::default(),
+            bytes_per_row: 16,
+            error: None,
+            edit: None,
+        }
+    }
+}
+
+impl Memory {
+    fn count(&self) -> usize {
+        self.bytes_per_row * ROWS
+    }
+
+    /// Read the shown range again, if any
+    pub fn refresh(&self) -> Option<MemoryAction> {
+        self.base.map(|base| MemoryAction::Read {
+            address: format!("0x{base:x}"),
+            count: self.count(),
+        })
+    }
+
+    /// Remember the current bytes to highlight changes, returning the read of the shown range
+    pub fn on_stopped(&mut self) -> Option<MemoryAction> {
+        self.previous = self.bytes.clone();
+        self.refresh()
+    }
+
+    /// Set the contents from the result of `-data-read-memory-bytes`
+    pub fn set_contents(&mut self, memory: &[Value]) {
+        self.bytes.clear();
+        self.base = None;
+        self.error = None;
+
+        for region in memory.iter().filter_map(Value::as_tuple) {
+            let Some(begin) = region.get_str("begin").and_then(parse_address) else {
+                continue;
+            };
+            let offset = region
+                .get_str("offset")
+                .and_then(parse_address)
+                .unwrap_or(0);
+            let base = begin.wrapping_sub(offset);
+            self.base = Some(self.base.map_or(base, |b| b.min(base)));
+
+            let contents = region.get_str("contents").unwrap_or_default().as_bytes();
+            for (i, pair) in contents.as_chunks::<2>().0.iter().enumerate() {
+                let byte = std::str::from_utf8(pair.as_slice())
+                    .ok()
+                    .and_then(|p| u8::from_str_radix(p, 16).ok());
+                if let Some(byte) = byte {
+                    self.bytes.insert(begin.wrapping_add(i as u64), byte);
+                }
+            }
+        }
+    }
+
+    /// Show why the memory could not be read
+    pub fn read_failed(&mut self, message: &str) {
+        self.bytes.clear();
+        self.error = Some(message.to_owned());
+    }
+
+    /// Forget the contents, keeping the layout settings
+    pub fn clear(&mut self) {
+        *self = Self {
+            address_input: std::mem::take(&mut self.address_input),
+            word_size: self.word_size,
+            endianness: self.endianness,
+            bytes_per_row: self.bytes_per_row,
+            ..Self::default()
+        };
+    }
+
+    /// The bytes of the word at `address`, `None` if any of them is unreadable
+    fn word(&self, address: u64) -> Option<Vec<u8>> {
+        (0..self.word_size as u64)
+            .map(|i| self.bytes.get(&address.wrapping_add(i)).copied())
+            .collect()
+    }
+
+    fn is_changed(&self, address: u64) -> bool {
+        self.previous
+            .get(&address)
+            .is_some_and(|previous| self.bytes.get(&address) != Some(previous))
+    }
+
+    /// Format the bytes of a word as a hexadecimal number in the selected byte order
+    fn format_word(&self, bytes: &[u8]) -> String {
+        let hex = |b: &u8| format!("{b:02x}");
+        match self.endianness {
+            Endianness::Little => bytes.iter().rev().map(hex).collect(),
+            Endianness::Big => bytes.iter().map(hex).collect(),
+        }
+    }
+
+    /// Parse an edited word into bytes in memory order
+    fn parse_word(&self, text: &str) -> Option<Vec<u8>> {
+        let text = text.trim();
+        let text = text.strip_prefix("0x").unwrap_or(text);
+        let value = u64::from_str_radix(text, 16).ok()?;
+        if self.word_size < 8 && value >> (self.word_size * 8) != 0 {
+            return None;
+        }
+
+        Some(match self.endianness {
+            Endianness::Little => value.to_le_bytes()[..self.word_size].to_vec(),
+            Endianness::Big => value.to_be_bytes()[8 - self.word_size..].to_vec(),
+        })
+    }
+
+    /// Render the memory view, returning the requested action
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<MemoryAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Address");
+            let response = ui.add(
+                TextEdit::singleline(&mut self.address_input)
+                    .font(TextStyle::Monospace)
+                    .hint_text("address or expression")
+                    .desired_width(200.0),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+            if (ui.button("Go").clicked() || submitted) && !self.address_input.trim().is_empty() {
+                action = Some(MemoryAction::Read {
+                    address: self.address_input.trim().to_owned(),
+                    count: self.count(),
+                });
+            }
+
+            if let Some(base) = self.base {
+                let count = self.count() as u64;
+                if ui.button("◀").on_hover_text("Previous page").clicked() {
+                    action = Some(MemoryAction::Read {
+                        address: format!("0x{:x}", base.saturating_sub(count)),
+                        count: self.count(),
+                    });
+                }
+                if ui.button("▶").on_hover_text("Next page").clicked() {
+                    action = Some(MemoryAction::Read {
+                        address: format!("0x{:x}", base.saturating_add(count)),
+                        count: self.count(),
+                    });
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Word");
+            for size in [1, 2, 4, 8] {
+                if ui
+                    .selectable_value(&mut self.word_size, size, size.to_string())
+                    .clicked()
+                {
+                    self.edit = None;
+                }
+            }
+
+            ui.separator();
+            ui.label("Endianness");
+            for endianness in Endianness::ALL {
+                ui.selectable_value(&mut self.endianness, endianness, endianness.label());
+            }
+
+            ui.separator();
+            ui.label("Bytes per row");
+            for bytes_per_row in [8, 16, 32] {
+                if ui
+                    .selectable_value(
+                        &mut self.bytes_per_row,
+                        bytes_per_row,
+                        bytes_per_row.to_string(),
+                    )
+                    .clicked()
+                {
+                    action = self.refresh();
+                }
+            }
+        });
+
+        ui.separator();
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+        let Some(base) = self.base else {
+            if self.error.is_none() {
+                ui.weak("No memory");
+            }
+            return action;
+        };
+
+        ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+            Grid::new("memory")
+                .striped(true)
+                .num_columns(2 + self.bytes_per_row / self.word_size)
+                .show(ui, |ui| {
+                    for row in 0..ROWS as u64 {
+                        let start = base.wrapping_add(row * self.bytes_per_row as u64);
+                        ui.label(RichText::new(format!("0x{start:016x}")).monospace().weak());
+
+                        for word in (0..self.bytes_per_row as u64).step_by(self.word_size) {
+                            let address = start.wrapping_add(word);
+                            if let Some(bytes) = self.word_ui(ui, address) {
+                                action = Some(MemoryAction::Write { address, bytes });
+                            }
+                        }
+
+                        self.ascii_ui(ui, start);
+                        ui.end_row();
+                    }
+                });
+        });
+
+        action
+    }
+
+    /// Render a word, returning the bytes to write after an edit
+    fn word_ui(&mut self, ui: &mut Ui, address: u64) -> Option<Vec<u8>> {
+        if let Some((edit_address, text)) = &mut self.edit
+            && *edit_address == address
+        {
+            let response = ui.add(
+                TextEdit::singleline(text)
+                    .font(TextStyle::Monospace)
+                    .desired_width(self.word_size as f32 * 2.0 * 8.0),
+            );
+            if response.lost_focus() {
+                let entered = ui.input(|i| i.key_pressed(Key::Enter));
+                let text = text.clone();
+                self.edit = None;
+                if entered {
+                    let bytes = self.parse_word(&text);
+                    if bytes.is_none() {
+                        self.error = Some(format!("Invalid value {text}"));
+                    }
+                    return bytes;
+                }
+            } else {
+                response.request_focus();
+            }
+            return None;
+        }
+
+        let Some(bytes) = self.word(address) else {
+            ui.label(
+                RichText::new("??".repeat(self.word_size))
+                    .monospace()
+                    .weak(),
+            );
+            return None;
+        };
+
+        let mut text = RichText::new(self.format_word(&bytes)).monospace();
+        if (0..self.word_size as u64).any(|i| self.is_changed(address.wrapping_add(i))) {
+            text = text.color(ui.visuals().warn_fg_color);
+        }
+        if ui
+            .label(text)
+            .on_hover_text(format!("0x{address:x}, double-click to edit"))
+            .double_clicked()
+        {
+            self.edit = Some((address, self.format_word(&bytes)));
+        }
+        None
+    }
+
+    fn ascii_ui(&self, ui: &mut Ui, start: u64) {
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            for i in 0..self.bytes_per_row as u64 {
+                let address = start.wrapping_add(i);
+                let c = match self.bytes.get(&address) {
+                    Some(b) if b.is_ascii_graphic() || *b == b' ' => char::from(*b),
+                    _ => '.',
+                };
+                let mut text = RichText::new(c.to_string()).monospace();
+                if self.is_changed(address) {
+                    text = text.color(ui.visuals().warn_fg_color);
+                }
+                ui.label(text);
+            }
+        });
+    }
+}
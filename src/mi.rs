@@ -0,0 +1,556 @@
+//! Parser for the GDB/MI machine interface output syntax
+//!
+//! See <https://sourceware.org/gdb/current/onlinedocs/gdb.html/GDB_002fMI-Output-Syntax.html>
+
+/// A named list of values, as found in result records and MI tuples
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tuple(pub Vec<(String, Value)>);
+
+impl Tuple {
+    /// Get the first value with the given name
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.0.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Get the first value with the given name as a string constant
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        self.get(name).and_then(Value::as_str)
+    }
+
+    /// Get the first value with the given name as a tuple
+    pub fn get_tuple(&self, name: &str) -> Option<&Self> {
+        self.get(name).and_then(Value::as_tuple)
+    }
+
+    /// Get the first value with the given name as a list
+    pub fn get_list(&self, name: &str) -> Option<&[Value]> {
+        self.get(name).and_then(Value::as_list)
+    }
+}
+
+/// A value in an MI record
+///
+/// Lists of results (`[frame={...},frame={...}]`) are stored as plain lists of
+/// their values, since GDB always repeats the same name for every element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Const(String),
+    Tuple(Tuple),
+    List(Vec<Self>),
+}
+
+impl Value {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Const(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_tuple(&self) -> Option<&Tuple> {
+        match self {
+            Self::Tuple(t) => Some(t),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Self]> {
+        match self {
+            Self::List(l) => Some(l),
+            _ => None,
+        }
+    }
+}
+
+/// The class of a result record (`^done`, `^error`, ...)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResultClass {
+    Done,
+    Running,
+    Connected,
+    Error,
+    Exit,
+}
+
+/// The kind of an async record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncKind {
+    /// `*` - execution state changes of the target
+    Exec,
+    /// `+` - progress information for slow operations
+    Status,
+    /// `=` - supplementary information for the client
+    Notify,
+}
+
+/// The kind of a stream record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamKind {
+    /// `~` - output that should be displayed as is in the console
+    Console,
+    /// `@` - output produced by the target program
+    Target,
+    /// `&` - GDB's internal log messages
+    Log,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultRecord {
+    pub token: Option<u64>,
+    pub class: ResultClass,
+    pub results: Tuple,
+}
+
+impl ResultRecord {
+    /// The error message of an `^error` record
+    pub fn error_message(&self) -> Option<&str> {
+        if self.class == ResultClass::Error {
+            self.results.get_str("msg")
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AsyncRecord {
+    pub token: Option<u64>,
+    pub kind: AsyncKind,
+    pub class: String,
+    pub results: Tuple,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamRecord {
+    pub kind: StreamKind,
+    pub text: String,
+}
+
+/// A single line of GDB/MI output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Record {
+    Result(ResultRecord),
+    Async(AsyncRecord),
+    Stream(StreamRecord),
+    /// The `(gdb)` prompt terminating a group of output records
+    Prompt,
+    /// A line that is not valid MI, e.g. output of the inferior sharing our terminal
+    Other(String),
+}
+
+impl Record {
+    /// Parse a single line of MI output
+    ///
+    /// Lines that are not valid MI are returned as [`Record::Other`].
+    pub fn parse(line: &str) -> Self {
+        let line = line.trim_end_matches(['\r', '\n']);
+        Parser::new(line)
+            .record()
+            .unwrap_or_else(|| Self::Other(line.to_owned()))
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: u8) -> Option<()> {
+        (self.peek()? == c).then(|| self.pos += 1)
+    }
+
+    fn record(&mut self) -> Option<Record> {
+        if self.rest().trim_end() == "(gdb)" {
+            return Some(Record::Prompt);
+        }
+
+        let token = self.token();
+        let record = match self.peek()? {
+            b'^' => {
+                self.pos += 1;
+                let class = match self.identifier()? {
+                    "done" => ResultClass::Done,
+                    "running" => ResultClass::Running,
+                    "connected" => ResultClass::Connected,
+                    "error" => ResultClass::Error,
+                    "exit" => ResultClass::Exit,
+                    _ => return None,
+                };
+                Record::Result(ResultRecord {
+                    token,
+                    class,
+                    results: self.results()?,
+                })
+            }
+            c @ (b'*' | b'+' | b'=') => {
+                self.pos += 1;
+                let kind = match c {
+                    b'*' => AsyncKind::Exec,
+                    b'+' => AsyncKind::Status,
+                    _ => AsyncKind::Notify,
+                };
+                let class = self.identifier()?.to_owned();
+                Record::Async(AsyncRecord {
+                    token,
+                    kind,
+                    class,
+                    results: self.results()?,
+                })
+            }
+            c @ (b'~' | b'@' | b'&') if token.is_none() => {
+                self.pos += 1;
+                let kind = match c {
+                    b'~' => StreamKind::Console,
+                    b'@' => StreamKind::Target,
+                    _ => StreamKind::Log,
+                };
+                Record::Stream(StreamRecord {
+                    kind,
+                    text: self.c_string()?,
+                })
+            }
+            _ => return None,
+        };
+
+        self.rest().is_empty().then_some(record)
+    }
+
+    fn token(&mut self) -> Option<u64> {
+        let len = self.rest().bytes().take_while(u8::is_ascii_digit).count();
+        if len == 0 {
+            return None;
+        }
+        let token = self.rest()[..len].parse().ok();
+        self.pos += len;
+        token
+    }
+
+    fn identifier(&mut self) -> Option<&'a str> {
+        let rest = self.rest();
+        let len = rest
+            .bytes()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_'))
+            .count();
+        if len == 0 {
+            return None;
+        }
+        self.pos += len;
+        Some(&rest[..len])
+    }
+
+    /// Parse `("," result)*` up to the end of the line
+    fn results(&mut self) -> Option<Tuple> {
+        let mut results = Vec::new();
+        while self.eat(b',').is_some() {
+            results.push(self.result()?);
+        }
+        Some(Tuple(results))
+    }
+
+    fn result(&mut self) -> Option<(String, Value)> {
+        let name = self.identifier()?.to_owned();
+        self.eat(b'=')?;
+        Some((name, self.value()?))
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        match self.peek()? {
+            b'"' => self.c_string().map(Value::Const),
+            b'{' => {
+                self.pos += 1;
+                let mut results = Vec::new();
+                if self.eat(b'}').is_none() {
+                    loop {
+                        results.push(self.result()?);
+                        if self.eat(b'}').is_some() {
+                            break;
+                        }
+                        self.eat(b',')?;
+                    }
+                }
+                Some(Value::Tuple(Tuple(results)))
+            }
+            b'[' => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                if self.eat(b']').is_none() {
+                    loop {
+                        // List elements are either plain values or `name=value` results
+                        let value = if matches!(self.peek()?, b'"' | b'{' | b'[') {
+                            self.value()?
+                        } else {
+                            self.result()?.1
+                        };
+                        values.push(value);
+                        if self.eat(b']').is_some() {
+                            break;
+                        }
+                        self.eat(b',')?;
+                    }
+                }
+                Some(Value::List(values))
+            }
+            _ => None,
+        }
+    }
+
+    /// Parse a C string literal, unescaping it
+    fn c_string(&mut self) -> Option<String> {
+        self.eat(b'"')?;
+        let bytes = self.input.as_bytes();
+        let mut out = Vec::new();
+        loop {
+            let c = *bytes.get(self.pos)?;
+            self.pos += 1;
+            match c {
+                b'"' => break,
+                b'\\' => {
+                    let e = *bytes.get(self.pos)?;
+                    self.pos += 1;
+                    match e {
+                        b'n' => out.push(b'\n'),
+                        b't' => out.push(b'\t'),
+                        b'r' => out.push(b'\r'),
+                        b'a' => out.push(0x07),
+                        b'b' => out.push(0x08),
+                        b'f' => out.push(0x0c),
+                        b'v' => out.push(0x0b),
+                        b'e' => out.push(0x1b),
+                        b'0'..=b'7' => {
+                            let mut value = u32::from(e - b'0');
+                            for _ in 0..2 {
+                                match bytes.get(self.pos) {
+                                    Some(d @ b'0'..=b'7') => {
+                                        value = value * 8 + u32::from(d - b'0');
+                                        self.pos += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+                            out.push(value as u8);
+                        }
+                        other => out.push(other),
+                    }
+                }
+                other => out.push(other),
+            }
+        }
+        Some(String::from_utf8_lossy(&out).into_owned())
+    }
+}
+
+/// Quote a string as a C string literal for use as an MI command parameter
+pub fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Parse a hexadecimal address such as `0x401136`
+pub fn parse_address(value: &str) -> Option<u64> {
+    u64::from_str_radix(value.strip_prefix("0x").unwrap_or(value), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(s: &str) -> Value {
+        Value::Const(s.to_owned())
+    }
+
+    fn tuple(results: &[(&str, Value)]) -> Tuple {
+        Tuple(
+            results
+                .iter()
+                .map(|(name, value)| ((*name).to_owned(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn result_record_with_token() {
+        assert_eq!(
+            Record::parse("42^done,value=\"1\"\n"),
+            Record::Result(ResultRecord {
+                token: Some(42),
+                class: ResultClass::Done,
+                results: tuple(&[("value", text("1"))]),
+            })
+        );
+        assert_eq!(
+            Record::parse("^running"),
+            Record::Result(ResultRecord {
+                token: None,
+                class: ResultClass::Running,
+                results: Tuple::default(),
+            })
+        );
+    }
+
+    #[test]
+    fn error_message() {
+        let Record::Result(record) = Record::parse("7^error,msg=\"No symbol \\\"x\\\".\"") else {
+            panic!("expected a result record");
+        };
+        assert_eq!(record.error_message(), Some("No symbol \"x\"."));
+    }
+
+    #[test]
+    fn nested_tuples_and_lists() {
+        let line = "*stopped,reason=\"breakpoint-hit\",frame={addr=\"0x401136\",\
+                    args=[{name=\"argc\",value=\"1\"}],line=\"5\"},stopped-threads=\"all\"";
+        assert_eq!(
+            Record::parse(line),
+            Record::Async(AsyncRecord {
+                token: None,
+                kind: AsyncKind::Exec,
+                class: "stopped".to_owned(),
+                results: tuple(&[
+                    ("reason", text("breakpoint-hit")),
+                    (
+                        "frame",
+                        Value::Tuple(tuple(&[
+                            ("addr", text("0x401136")),
+                            (
+                                "args",
+                                Value::List(vec![Value::Tuple(tuple(&[
+                                    ("name", text("argc")),
+                                    ("value", text("1")),
+                                ]))]),
+                            ),
+                            ("line", text("5")),
+                        ])),
+                    ),
+                    ("stopped-threads", text("all")),
+                ]),
+            })
+        );
+    }
+
+    #[test]
+    fn list_of_results() {
+        let Record::Result(record) =
+            Record::parse("^done,stack=[frame={level=\"0\"},frame={level=\"1\"}],empty=[],t={}")
+        else {
+            panic!("expected a result record");
+        };
+        assert_eq!(
+            record.results.get_list("stack"),
+            Some(
+                [
+                    Value::Tuple(tuple(&[("level", text("0"))])),
+                    Value::Tuple(tuple(&[("level", text("1"))])),
+                ]
+                .as_slice()
+            )
+        );
+        assert_eq!(record.results.get_list("empty"), Some([].as_slice()));
+        assert_eq!(record.results.get_tuple("t"), Some(&Tuple::default()));
+    }
+
+    #[test]
+    fn c_string_escapes() {
+        assert_eq!(
+            Record::parse(r#"~"a\tb\\c\"d\n""#),
+            Record::Stream(StreamRecord {
+                kind: StreamKind::Console,
+                text: "a\tb\\c\"d\n".to_owned(),
+            })
+        );
+        // Octal escapes are bytes, forming UTF-8 together
+        assert_eq!(
+            Record::parse(r#"@"\303\251\0331m\7""#),
+            Record::Stream(StreamRecord {
+                kind: StreamKind::Target,
+                text: "\u{e9}\x1b1m\x07".to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn stream_records() {
+        assert_eq!(
+            Record::parse("&\"warning\\n\""),
+            Record::Stream(StreamRecord {
+                kind: StreamKind::Log,
+                text: "warning\n".to_owned(),
+            })
+        );
+        assert_eq!(
+            Record::parse("=thread-group-added,id=\"i1\""),
+            Record::Async(AsyncRecord {
+                token: None,
+                kind: AsyncKind::Notify,
+                class: "thread-group-added".to_owned(),
+                results: tuple(&[("id", text("i1"))]),
+            })
+        );
+    }
+
+    #[test]
+    fn prompt() {
+        assert_eq!(Record::parse("(gdb) \n"), Record::Prompt);
+        assert_eq!(Record::parse("(gdb)"), Record::Prompt);
+    }
+
+    #[test]
+    fn other_lines() {
+        for line in [
+            "Hello, world!",
+            "",
+            "^unknown",
+            "^done,value=\"unterminated",
+            "^done,value=\"1\" trailing",
+            "1~\"stream records have no token\"",
+        ] {
+            assert_eq!(
+                Record::parse(line),
+                Record::Other(line.to_owned()),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn quote_round_trip() {
+        let s = "say \"hi\"\\\n\t";
+        assert_eq!(
+            Record::parse(&format!("~{}", quote(s))),
+            Record::Stream(StreamRecord {
+                kind: StreamKind::Console,
+                text: s.to_owned(),
+            })
+        );
+    }
+
+    #[test]
+    fn address() {
+        assert_eq!(parse_address("0x401136"), Some(0x0040_1136));
+        assert_eq!(parse_address("ff"), Some(0xff));
+        assert_eq!(parse_address("<optimized out>"), None);
+    }
+}
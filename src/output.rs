@@ -0,0 +1,540 @@
+use egui::text::LayoutJob;
+use egui::{Color32, FontId, Galley, Key, ScrollArea, TextEdit, TextFormat, TextStyle, Ui};
+use nix::fcntl::OFlag;
+use nix::pty;
+use std::error::Error;
+use std::fs::{File, OpenOptions};
+use std::io::{Read as _, Write as _};
+use std::os::fd::OwnedFd;
+use std::os::unix::fs::OpenOptionsExt as _;
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Output kept in the Output tab, older output is dropped
+const MAX_LEN: usize = 1 << 20;
+
+/// A pseudoterminal the inferior reads and writes instead of GDB's own terminal
+#[derive(Debug)]
+pub struct InferiorTty {
+    master: File,
+    /// Kept open so the terminal survives the inferior exiting between runs
+    _slave: File,
+    path: String,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl InferiorTty {
+    /// Open a terminal, repainting the context whenever the inferior writes to it
+    pub fn open(ctx: &egui::Context) -> Result<Self, Box<dyn Error>> {
+        let master = pty::posix_openpt(OFlag::O_RDWR | OFlag::O_NOCTTY)?;
+        pty::grantpt(&master)?;
+        pty::unlockpt(&master)?;
+        let path = pty::ptsname_r(&master)?;
+
+        let slave = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(OFlag::O_NOCTTY.bits())
+            .open(&path)?;
+
+        let master = File::from(OwnedFd::from(master));
+        let mut reader = master.try_clone()?;
+        let (sender, receiver) = mpsc::channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            loop {
+                match reader.read(&mut buffer) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        if sender.send(buffer[..n].to_vec()).is_err() {
+                            break;
+                        }
+                        ctx.request_repaint();
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            master,
+            _slave: slave,
+            path,
+            receiver,
+        })
+    }
+
+    /// The device path passed to `-inferior-tty-set`
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Send input to the inferior
+    pub fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        self.master.write_all(bytes)
+    }
+
+    /// Take the output written since the last call
+    pub fn read(&self) -> Vec<u8> {
+        self.receiver.try_iter().flatten().collect()
+    }
+}
+
+/// Colors and attributes set by ANSI SGR escape sequences
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    foreground: Option<Color32>,
+    background: Option<Color32>,
+    bold: bool,
+    underline: bool,
+}
+
+/// The font and default colors a [`Galley`] was laid out with
+type LayoutKey = (FontId, Color32, Color32);
+
+/// Terminal output split into styled spans
+#[derive(Debug, Default)]
+pub struct AnsiText {
+    spans: Vec<(Style, String)>,
+    style: Style,
+    /// An escape sequence or UTF-8 character split across reads
+    pending: Vec<u8>,
+    len: usize,
+    /// The laid out text, dropped when the text changes
+    galley: Option<(LayoutKey, Arc<Galley>)>,
+}
+
+impl AnsiText {
+    /// Append raw terminal output
+    pub fn push(&mut self, bytes: &[u8]) {
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(bytes);
+
+        let mut text_start = 0;
+        let mut i = 0;
+        while i < data.len() {
+            if data[i] != 0x1b {
+                i += 1;
+                continue;
+            }
+
+            self.push_text(&data[text_start..i]);
+            let Some(end) = escape_end(&data[i..]) else {
+                self.pending = data[i..].to_vec();
+                return;
+            };
+            if data[i + 1] == b'[' && data[i + end - 1] == b'm' {
+                self.apply_sgr(&String::from_utf8_lossy(&data[i + 2..i + end - 1]));
+            }
+            i += end;
+            text_start = i;
+        }
+
+        // A character cut in half by the read is completed by the next one
+        let tail = &data[text_start..];
+        let complete = match std::str::from_utf8(tail) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => tail.len(),
+        };
+        self.push_text(&tail[..complete]);
+        self.pending = tail[complete..].to_vec();
+    }
+
+    fn push_text(&mut self, bytes: &[u8]) {
+        // The terminal turns newlines into CRLF, and other control characters are not shown
+        let text: String = String::from_utf8_lossy(bytes)
+            .chars()
+            .filter(|&c| !c.is_control() || c == '\n' || c == '\t')
+            .collect();
+        if text.is_empty() {
+            return;
+        }
+
+        self.galley = None;
+        self.len += text.len();
+        match self.spans.last_mut() {
+            Some((style, last)) if *style == self.style => last.push_str(&text),
+            _ => self.spans.push((self.style, text)),
+        }
+
+        while self.len > MAX_LEN && self.spans.len() > 1 {
+            let (_, dropped) = self.spans.remove(0);
+            self.len -= dropped.len();
+        }
+
+        // Output in a single style is one span, cut from its front at the next line
+        if self.len > MAX_LEN
+            && let Some((_, first)) = self.spans.first_mut()
+        {
+            let mut start = self.len - MAX_LEN;
+            while !first.is_char_boundary(start) {
+                start += 1;
+            }
+            let cut = first[start..].find('\n').map_or(start, |i| start + i + 1);
+            first.drain(..cut);
+            self.len -= cut;
+        }
+    }
+
+    /// Apply the parameters of a `CSI ... m` sequence
+    fn apply_sgr(&mut self, params: &str) {
+        let mut codes = params
+            .split(';')
+            .map(|code| code.parse::<u8>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => self.style = Style::default(),
+                1 => self.style.bold = true,
+                4 => self.style.underline = true,
+                22 => self.style.bold = false,
+                24 => self.style.underline = false,
+                30..=37 => self.style.foreground = Some(palette(code - 30)),
+                38 => self.style.foreground = extended_color(&mut codes),
+                39 => self.style.foreground = None,
+                40..=47 => self.style.background = Some(palette(code - 40)),
+                48 => self.style.background = extended_color(&mut codes),
+                49 => self.style.background = None,
+                90..=97 => self.style.foreground = Some(palette(code - 90 + 8)),
+                100..=107 => self.style.background = Some(palette(code - 100 + 8)),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// The laid out text, only laid out again when the text or the visuals changed
+    fn galley(&mut self, ui: &Ui) -> Arc<Galley> {
+        let key = (
+            TextStyle::Monospace.resolve(ui.style()),
+            ui.visuals().text_color(),
+            ui.visuals().strong_text_color(),
+        );
+        match &self.galley {
+            Some((cached, galley)) if *cached == key => Arc::clone(galley),
+            _ => {
+                let galley = ui.fonts(|fonts| fonts.layout_job(self.layout_job(&key)));
+                self.galley = Some((key, Arc::clone(&galley)));
+                galley
+            }
+        }
+    }
+
+    fn layout_job(&self, (font_id, text_color, strong_color): &LayoutKey) -> LayoutJob {
+        let mut job = LayoutJob::default();
+        for (style, text) in &self.spans {
+            let color = style.foreground.unwrap_or(if style.bold {
+                *strong_color
+            } else {
+                *text_color
+            });
+            job.append(
+                text,
+                0.0,
+                TextFormat {
+                    font_id: font_id.clone(),
+                    color,
+                    background: style.background.unwrap_or_default(),
+                    underline: if style.underline {
+                        egui::Stroke::new(1.0_f32, color)
+                    } else {
+                        egui::Stroke::NONE
+                    },
+                    ..Default::default()
+                },
+            );
+        }
+        job
+    }
+}
+
+/// The length of the escape sequence at the start of `data`, `None` when it is incomplete
+fn escape_end(data: &[u8]) -> Option<usize> {
+    match data.get(1)? {
+        // CSI, ended by a byte in 0x40..=0x7e
+        b'[' => data[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map(|end| end + 3),
+        // OSC, ended by BEL or ESC \
+        b']' => (2..data.len()).find_map(|i| match data[i] {
+            0x07 => Some(i + 1),
+            0x1b if data.get(i + 1) == Some(&b'\\') => Some(i + 2),
+            _ => None,
+        }),
+        // Others, e.g. `ESC ( B`, may have intermediate bytes in 0x20..=0x2f before the final one
+        _ => data[1..]
+            .iter()
+            .position(|b| !(0x20..=0x2f).contains(b))
+            .map(|end| end + 2),
+    }
+}
+
+/// A color of the 256 color palette
+fn palette(index: u8) -> Color32 {
+    const BASIC: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 49, 49),
+        (13, 188, 121),
+        (229, 229, 16),
+        (36, 114, 200),
+        (188, 63, 188),
+        (17, 168, 205),
+        (229, 229, 229),
+        (102, 102, 102),
+        (241, 76, 76),
+        (35, 209, 139),
+        (245, 245, 67),
+        (59, 142, 234),
+        (214, 112, 214),
+        (41, 184, 219),
+        (255, 255, 255),
+    ];
+
+    match index {
+        0..=15 => {
+            let (r, g, b) = BASIC[index as usize];
+            Color32::from_rgb(r, g, b)
+        }
+        16..=231 => {
+            let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+            let index = index - 16;
+            Color32::from_rgb(level(index / 36), level(index / 6 % 6), level(index % 6))
+        }
+        _ => {
+            let gray = 8 + (index - 232) * 10;
+            Color32::from_rgb(gray, gray, gray)
+        }
+    }
+}
+
+/// Read the color of a `38;5;n` or `38;2;r;g;b` sequence
+fn extended_color(codes: &mut impl Iterator<Item = u8>) -> Option<Color32> {
+    match codes.next()? {
+        5 => codes.next().map(palette),
+        2 => Some(Color32::from_rgb(
+            codes.next()?,
+            codes.next()?,
+            codes.next()?,
+        )),
+        _ => None,
+    }
+}
+
+/// State of the Output tab, showing the terminal of the inferior
+#[derive(Debug, Default)]
+pub struct Output {
+    tty: Option<InferiorTty>,
+    text: AnsiText,
+    input: String,
+    error: Option<String>,
+}
+
+impl Output {
+    /// Open the terminal once, returning its path for `-inferior-tty-set`
+    pub fn open_tty(&mut self, ctx: &egui::Context) -> Result<String, Box<dyn Error>> {
+        if let Some(tty) = &self.tty {
+            return Ok(tty.path().to_owned());
+        }
+        let tty = InferiorTty::open(ctx)?;
+        let path = tty.path().to_owned();
+        self.tty = Some(tty);
+        Ok(path)
+    }
+
+    /// Take the output written by the inferior since the last update
+    pub fn poll(&mut self) {
+        if let Some(tty) = &self.tty {
+            let bytes = tty.read();
+            if !bytes.is_empty() {
+                self.text.push(&bytes);
+            }
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        let Some(tty) = &mut self.tty else {
+            self.error = Some("No terminal for the program".to_owned());
+            return;
+        };
+        self.error = tty
+            .write(bytes)
+            .err()
+            .map(|e| format!("Failed to send input: {e}"));
+    }
+
+    pub fn ui(&mut self, ui: &mut Ui) {
+        let input_height = ui.spacing().interact_size.y + ui.spacing().item_spacing.y * 2.0;
+        ScrollArea::both()
+            .auto_shrink(false)
+            .stick_to_bottom(true)
+            .max_height(ui.available_height() - input_height)
+            .show(ui, |ui| {
+                if self.text.is_empty() {
+                    ui.weak("No program output");
+                } else {
+                    ui.label(self.text.galley(ui));
+                }
+            });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                TextEdit::singleline(&mut self.input)
+                    .font(TextStyle::Monospace)
+                    .hint_text("input")
+                    .desired_width(ui.available_width() - 120.0),
+            );
+            if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+                let line = format!("{}\n", std::mem::take(&mut self.input));
+                self.write(line.as_bytes());
+                response.request_focus();
+            }
+            if ui.button("EOF").on_hover_text("Send Ctrl-D").clicked() {
+                self.write(&[0x04]);
+            }
+            if ui.button("Clear").clicked() {
+                self.text.clear();
+            }
+        });
+
+        if let Some(error) = &self.error {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(ansi: &AnsiText) -> Vec<(Style, &str)> {
+        ansi.spans
+            .iter()
+            .map(|(style, text)| (*style, text.as_str()))
+            .collect()
+    }
+
+    fn fg(color: Color32) -> Style {
+        Style {
+            foreground: Some(color),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn plain_text_drops_carriage_returns() {
+        let mut ansi = AnsiText::default();
+        ansi.push(b"hello\r\n\tworld\x07");
+        assert_eq!(text(&ansi), vec![(Style::default(), "hello\n\tworld")]);
+    }
+
+    #[test]
+    fn sgr_colors_and_reset() {
+        let mut ansi = AnsiText::default();
+        ansi.push(b"\x1b[31mred\x1b[1;4mbold\x1b[0m plain\x1b[mmore");
+        let red = palette(1);
+        assert_eq!(
+            text(&ansi),
+            vec![
+                (fg(red), "red"),
+                (
+                    Style {
+                        bold: true,
+                        underline: true,
+                        ..fg(red)
+                    },
+                    "bold"
+                ),
+                (Style::default(), " plainmore"),
+            ]
+        );
+    }
+
+    #[test]
+    fn extended_colors() {
+        let mut ansi = AnsiText::default();
+        ansi.push(b"\x1b[38;5;196ma\x1b[38;2;1;2;3;48;5;232mb\x1b[39;49;92mc");
+        assert_eq!(
+            text(&ansi),
+            vec![
+                (fg(Color32::from_rgb(255, 0, 0)), "a"),
+                (
+                    Style {
+                        background: Some(Color32::from_rgb(8, 8, 8)),
+                        ..fg(Color32::from_rgb(1, 2, 3))
+                    },
+                    "b"
+                ),
+                (fg(palette(10)), "c"),
+            ]
+        );
+    }
+
+    #[test]
+    fn escape_split_across_reads() {
+        let mut ansi = AnsiText::default();
+        ansi.push(b"a\x1b[3");
+        assert_eq!(text(&ansi), vec![(Style::default(), "a")]);
+        ansi.push(b"2mb");
+        assert_eq!(
+            text(&ansi),
+            vec![(Style::default(), "a"), (fg(palette(2)), "b")]
+        );
+    }
+
+    #[test]
+    fn utf8_split_across_reads() {
+        let mut ansi = AnsiText::default();
+        ansi.push(&[b'x', 0xc3]);
+        ansi.push(&[0xa9, b'y']);
+        assert_eq!(text(&ansi), vec![(Style::default(), "x\u{e9}y")]);
+    }
+
+    #[test]
+    fn other_escapes_are_dropped() {
+        let mut ansi = AnsiText::default();
+        ansi.push(b"\x1b]0;title\x07a\x1b[2Kb\x1b]8;;url\x1b\\c\x1b(Bd");
+        assert_eq!(text(&ansi), vec![(Style::default(), "abcd")]);
+    }
+
+    #[test]
+    fn old_output_is_dropped() {
+        let mut ansi = AnsiText::default();
+        let chunk = vec![b'a'; MAX_LEN / 2 + 1];
+        ansi.push(b"\x1b[31m");
+        ansi.push(&chunk);
+        ansi.push(b"\x1b[32m");
+        ansi.push(&chunk);
+        ansi.push(b"\x1b[33m");
+        ansi.push(&chunk);
+        assert_eq!(ansi.spans.len(), 1);
+        assert_eq!(ansi.len, chunk.len());
+
+        ansi.clear();
+        assert!(ansi.is_empty());
+    }
+
+    #[test]
+    fn old_output_in_one_style_is_dropped() {
+        let mut ansi = AnsiText::default();
+        let line = format!("{}\n", "\u{e9}".repeat(99));
+        for _ in 0..(MAX_LEN / line.len() + 10) {
+            ansi.push(line.as_bytes());
+        }
+        assert_eq!(ansi.spans.len(), 1);
+        assert!(ansi.len <= MAX_LEN);
+        let (_, text) = &ansi.spans[0];
+        assert_eq!(text.len(), ansi.len);
+        // Cut at the start of a line
+        assert!(text.starts_with(&line));
+    }
+}
@@ -0,0 +1,97 @@
+//! Named launch profiles, read from a `gdbr.toml` project file
+//!
+//! ```toml
+//! [profiles.server]
+//! executable = "target/debug/server"
+//! args = "--port 8080"
+//! cwd = "."
+//! gdb_commands = ["set print pretty on"]
+//! breakpoints = ["main", "src/server.rs:42"]
+//! watches = ["requests.len"]
+//! tabs = ["Source", "Console", "Locals", "Stack"]
+//!
+//! [profiles.server.env]
+//! RUST_LOG = "debug"
+//! ```
+
+use crate::launch::{EnvVar, LaunchConfig};
+use crate::tabs::Tab;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+
+/// The project file, looked up in the working directory
+pub const PROJECT_FILE: &str = "gdbr.toml";
+
+/// Everything needed to start debugging a program the same way again
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Profile {
+    /// Relative to the project file, the open executable when unset
+    pub executable: Option<PathBuf>,
+    pub args: String,
+    pub env: BTreeMap<String, String>,
+    pub cwd: String,
+    pub stdin: String,
+    pub stdout: String,
+    /// Sent to GDB once it started, before the program runs
+    pub gdb_commands: Vec<String>,
+    /// Breakpoint locations, e.g. `main` or `src/main.c:42`
+    pub breakpoints: Vec<String>,
+    pub watches: Vec<String>,
+    /// The tabs to show, keeping the current layout when empty
+    pub tabs: Vec<Tab>,
+}
+
+impl Profile {
+    pub fn launch_config(&self) -> LaunchConfig {
+        LaunchConfig {
+            args: self.args.clone(),
+            env: self
+                .env
+                .iter()
+                .map(|(name, value)| EnvVar {
+                    name: name.clone(),
+                    value: value.clone(),
+                })
+                .collect(),
+            cwd: self.cwd.clone(),
+            stdin: self.stdin.clone(),
+            stdout: self.stdout.clone(),
+        }
+    }
+}
+
+/// The profiles of a project file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Project {
+    /// The directory of the project file
+    #[serde(skip)]
+    pub dir: PathBuf,
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+impl Project {
+    /// Read the project file of a directory, `None` when there is none
+    pub fn load(dir: &Path) -> Result<Option<Self>, Box<dyn Error>> {
+        let path = dir.join(PROJECT_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let mut project: Self = toml::from_str(&std::fs::read_to_string(&path)?)
+            .map_err(|e| format!("{}: {e}", path.display()))?;
+        project.dir = dir.to_path_buf();
+        Ok(Some(project))
+    }
+
+    /// The executable of a profile, resolved against the project directory
+    pub fn executable(&self, profile: &Profile) -> Option<PathBuf> {
+        profile
+            .executable
+            .as_ref()
+            .map(|executable| self.dir.join(executable))
+    }
+}
@@ -0,0 +1,424 @@
+use crate::mi::Value;
+use egui::{CollapsingHeader, Grid, Key, RichText, TextEdit, Ui};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Register groups shown as separate sections of the Registers tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RegisterGroup {
+    General,
+    Flags,
+    Vector,
+    FloatingPoint,
+}
+
+impl RegisterGroup {
+    pub const ALL: [Self; 4] = [
+        Self::General,
+        Self::Flags,
+        Self::Vector,
+        Self::FloatingPoint,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::General => "General purpose",
+            Self::Flags => "Flags",
+            Self::Vector => "Vector",
+            Self::FloatingPoint => "Floating point",
+        }
+    }
+
+    /// Classify a register by its name, which means different things on each architecture
+    pub fn of(arch: Architecture, name: &str) -> Self {
+        let digits_after = |prefixes: &[&str]| {
+            prefixes.iter().any(|prefix| {
+                name.strip_prefix(prefix)
+                    .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+            })
+        };
+
+        match arch {
+            Architecture::X86 => {
+                if name.contains("flags") {
+                    Self::Flags
+                } else if digits_after(&["xmm", "ymm", "zmm", "k"]) || name.starts_with("mxcsr") {
+                    Self::Vector
+                } else if digits_after(&["st"])
+                    || matches!(
+                        name,
+                        "fctrl" | "fstat" | "ftag" | "fiseg" | "fioff" | "foseg" | "fooff" | "fop"
+                    )
+                {
+                    Self::FloatingPoint
+                } else {
+                    Self::General
+                }
+            }
+            Architecture::AArch64 => {
+                if matches!(name, "cpsr" | "nzcv") {
+                    Self::Flags
+                } else if digits_after(&["v", "q", "z", "p"]) || matches!(name, "ffr" | "vg") {
+                    Self::Vector
+                } else if digits_after(&["d", "s", "h"]) || matches!(name, "fpsr" | "fpcr") {
+                    Self::FloatingPoint
+                } else {
+                    Self::General
+                }
+            }
+            Architecture::Arm => {
+                if matches!(name, "cpsr" | "xpsr") {
+                    Self::Flags
+                } else if digits_after(&["q"]) {
+                    Self::Vector
+                } else if digits_after(&["s", "d", "f"]) || matches!(name, "fpscr" | "fps") {
+                    Self::FloatingPoint
+                } else {
+                    Self::General
+                }
+            }
+            Architecture::RiscV => {
+                if digits_after(&["v"]) || matches!(name, "vl" | "vtype" | "vstart" | "vlenb") {
+                    Self::Vector
+                } else if digits_after(&["f", "ft", "fs", "fa"])
+                    || matches!(name, "fcsr" | "fflags" | "frm")
+                {
+                    Self::FloatingPoint
+                } else {
+                    Self::General
+                }
+            }
+            Architecture::Other => {
+                if name.contains("flags") {
+                    Self::Flags
+                } else {
+                    Self::General
+                }
+            }
+        }
+    }
+}
+
+/// The register naming scheme of the target, from the `arch` field of frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Architecture {
+    X86,
+    AArch64,
+    Arm,
+    RiscV,
+    /// Unknown, showing every register but flags as general purpose
+    #[default]
+    Other,
+}
+
+impl Architecture {
+    /// Parse a BFD architecture name such as `i386:x86-64` or `riscv:rv64`
+    pub fn from_name(name: &str) -> Self {
+        if name.starts_with("i386") || name.starts_with("i8086") {
+            Self::X86
+        } else if name.starts_with("aarch64") {
+            Self::AArch64
+        } else if name.starts_with("arm") {
+            Self::Arm
+        } else if name.starts_with("riscv") {
+            Self::RiscV
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// Format used for `-data-list-register-values`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RegisterFormat {
+    #[default]
+    Hexadecimal,
+    Decimal,
+    Natural,
+}
+
+impl RegisterFormat {
+    pub const ALL: [Self; 3] = [Self::Hexadecimal, Self::Decimal, Self::Natural];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Hexadecimal => "Hex",
+            Self::Decimal => "Decimal",
+            Self::Natural => "Natural",
+        }
+    }
+
+    /// The format letter understood by `-data-list-register-values`
+    pub fn mi_format(self) -> char {
+        match self {
+            Self::Hexadecimal => 'x',
+            Self::Decimal => 'd',
+            Self::Natural => 'N',
+        }
+    }
+}
+
+/// Names of the bits of well known flags registers, `(bit, name)`
+fn flag_bits(name: &str) -> &'static [(u32, &'static str)] {
+    match name {
+        "eflags" | "rflags" => &[
+            (0, "CF"),
+            (2, "PF"),
+            (4, "AF"),
+            (6, "ZF"),
+            (7, "SF"),
+            (8, "TF"),
+            (9, "IF"),
+            (10, "DF"),
+            (11, "OF"),
+            (14, "NT"),
+            (16, "RF"),
+            (17, "VM"),
+            (18, "AC"),
+            (19, "VIF"),
+            (20, "VIP"),
+            (21, "ID"),
+        ],
+        "cpsr" | "xpsr" | "nzcv" => &[
+            (31, "N"),
+            (30, "Z"),
+            (29, "C"),
+            (28, "V"),
+            (27, "Q"),
+            (9, "E"),
+            (8, "A"),
+            (7, "I"),
+            (6, "F"),
+            (5, "T"),
+        ],
+        _ => &[],
+    }
+}
+
+/// Parse a register value printed in hexadecimal or decimal
+fn parse_integer(value: &str) -> Option<u64> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value
+            .parse::<u64>()
+            .ok()
+            .or_else(|| value.parse::<i64>().ok().map(|v| v as u64)),
+    }
+}
+
+/// An action requested from the Registers tab
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegisterAction {
+    /// Fetch the values again, e.g. after changing the format
+    Refresh,
+    /// Assign a new value to the named register
+    Assign(String, String),
+}
+
+/// State of the Registers tab
+#[derive(Debug, Default)]
+pub struct Registers {
+    /// Register names, indexed by register number
+    names: Vec<String>,
+    arch: Architecture,
+    values: BTreeMap<usize, String>,
+    changed: BTreeSet<usize>,
+    format: RegisterFormat,
+    edit: Option<(usize, String)>,
+}
+
+impl Registers {
+    pub fn has_names(&self) -> bool {
+        !self.names.is_empty()
+    }
+
+    pub fn format(&self) -> RegisterFormat {
+        self.format
+    }
+
+    /// Set the architecture from the `arch` field of a frame
+    pub fn set_arch(&mut self, name: &str) {
+        self.arch = Architecture::from_name(name);
+    }
+
+    /// Set the names from the result of `-data-list-register-names`
+    pub fn set_names(&mut self, names: &[Value]) {
+        self.names = names
+            .iter()
+            .map(|n| n.as_str().unwrap_or_default().to_owned())
+            .collect();
+    }
+
+    /// Set the values from the result of `-data-list-register-values`
+    pub fn set_values(&mut self, values: &[Value]) {
+        self.values = values
+            .iter()
+            .filter_map(Value::as_tuple)
+            .filter_map(|v| {
+                let number = v.get_str("number")?.parse().ok()?;
+                Some((number, v.get_str("value")?.to_owned()))
+            })
+            .collect();
+    }
+
+    /// Set the registers changed since the last stop from `-data-list-changed-registers`
+    pub fn set_changed(&mut self, changed: &[Value]) {
+        self.changed = changed
+            .iter()
+            .filter_map(|n| n.as_str()?.parse().ok())
+            .collect();
+    }
+
+    /// Forget everything, e.g. when a new GDB process is started
+    pub fn clear(&mut self) {
+        *self = Self {
+            format: self.format,
+            ..Self::default()
+        };
+    }
+
+    /// Render the register groups, returning the requested action
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<RegisterAction> {
+        let mut action = None;
+
+        ui.horizontal(|ui| {
+            ui.label("Format");
+            for format in RegisterFormat::ALL {
+                if ui
+                    .selectable_value(&mut self.format, format, format.label())
+                    .clicked()
+                {
+                    action = Some(RegisterAction::Refresh);
+                }
+            }
+        });
+
+        ui.separator();
+
+        if self.values.is_empty() {
+            ui.weak("No registers");
+            return action;
+        }
+
+        egui::ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+            for group in RegisterGroup::ALL {
+                let registers: Vec<(usize, String, String)> = self
+                    .values
+                    .iter()
+                    .filter_map(|(&number, value)| {
+                        let name = self.names.get(number)?.as_str();
+                        (!name.is_empty() && RegisterGroup::of(self.arch, name) == group)
+                            .then_some((number, name.to_owned(), value.clone()))
+                    })
+                    .collect();
+                if registers.is_empty() {
+                    continue;
+                }
+
+                CollapsingHeader::new(group.title())
+                    .default_open(matches!(
+                        group,
+                        RegisterGroup::General | RegisterGroup::Flags
+                    ))
+                    .show(ui, |ui| {
+                        Grid::new(group.title())
+                            .striped(true)
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for (number, name, value) in registers {
+                                    ui.monospace(&name);
+                                    self.value_ui(ui, number, &name, &value, &mut action);
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+
+        action
+    }
+
+    fn value_ui(
+        &mut self,
+        ui: &mut Ui,
+        number: usize,
+        name: &str,
+        value: &str,
+        action: &mut Option<RegisterAction>,
+    ) {
+        if let Some((edit_number, text)) = &mut self.edit
+            && *edit_number == number
+        {
+            let response = ui.add(TextEdit::singleline(text).font(egui::TextStyle::Monospace));
+            if response.lost_focus() {
+                if ui.input(|i| i.key_pressed(Key::Enter)) {
+                    *action = Some(RegisterAction::Assign(name.to_owned(), text.clone()));
+                }
+                self.edit = None;
+            } else {
+                response.request_focus();
+            }
+            return;
+        }
+
+        ui.vertical(|ui| {
+            let mut text = RichText::new(value).monospace();
+            if self.changed.contains(&number) {
+                text = text.color(ui.visuals().warn_fg_color);
+            }
+            if ui
+                .label(text)
+                .on_hover_text("Double-click to edit")
+                .double_clicked()
+            {
+                self.edit = Some((number, value.to_owned()));
+            }
+
+            let bits = flag_bits(name);
+            if let Some(flags) = parse_integer(value).filter(|_| !bits.is_empty()) {
+                ui.horizontal_wrapped(|ui| {
+                    for (bit, flag) in bits {
+                        let flag = RichText::new(*flag).monospace().small();
+                        if flags & (1 << bit) != 0 {
+                            ui.label(flag.strong());
+                        } else {
+                            ui.label(flag.weak());
+                        }
+                    }
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_depend_on_the_architecture() {
+        let riscv = Architecture::from_name("riscv:rv64");
+        assert_eq!(RegisterGroup::of(riscv, "s1"), RegisterGroup::General);
+        assert_eq!(
+            RegisterGroup::of(riscv, "fs1"),
+            RegisterGroup::FloatingPoint
+        );
+        assert_eq!(RegisterGroup::of(riscv, "v3"), RegisterGroup::Vector);
+
+        let arm = Architecture::from_name("armv7");
+        assert_eq!(RegisterGroup::of(arm, "s1"), RegisterGroup::FloatingPoint);
+        assert_eq!(RegisterGroup::of(arm, "cpsr"), RegisterGroup::Flags);
+
+        let aarch64 = Architecture::from_name("aarch64");
+        assert_eq!(RegisterGroup::of(aarch64, "p0"), RegisterGroup::Vector);
+        assert_eq!(RegisterGroup::of(aarch64, "x0"), RegisterGroup::General);
+
+        let x86 = Architecture::from_name("i386:x86-64");
+        assert_eq!(RegisterGroup::of(x86, "eflags"), RegisterGroup::Flags);
+        assert_eq!(RegisterGroup::of(x86, "k1"), RegisterGroup::Vector);
+        assert_eq!(RegisterGroup::of(x86, "st0"), RegisterGroup::FloatingPoint);
+        assert_eq!(RegisterGroup::of(x86, "r8"), RegisterGroup::General);
+
+        let other = Architecture::from_name("mips");
+        assert_eq!(RegisterGroup::of(other, "p1"), RegisterGroup::General);
+    }
+}
@@ -0,0 +1,248 @@
+use egui::{Color32, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// How long to wait before reconnecting to a lost gdbserver
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// How long to wait for `-target-select`, GDB itself retries TCP connections for 15 seconds
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Check if GDB output reports that the connection to the gdbserver was lost
+pub fn is_connection_lost(text: &str) -> bool {
+    text.contains("Remote connection closed") || text.contains("Remote communication error")
+}
+
+/// A gdbserver to connect to
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct RemoteTarget {
+    /// `host:port` or a serial device
+    pub address: String,
+    /// Use `target extended-remote`, which can run and attach to processes
+    pub extended: bool,
+    /// Where the target libraries are found, unset when empty
+    pub sysroot: String,
+    /// Extra directories searched for shared libraries, unset when empty
+    pub solib_search_path: String,
+    /// Reconnect when the connection is lost or refused
+    pub reconnect: bool,
+}
+
+impl Default for RemoteTarget {
+    fn default() -> Self {
+        Self {
+            address: String::new(),
+            extended: false,
+            sysroot: String::new(),
+            solib_search_path: String::new(),
+            reconnect: true,
+        }
+    }
+}
+
+impl RemoteTarget {
+    /// Commands configuring GDB before connecting
+    pub fn setup_commands(&self) -> Vec<String> {
+        let mut commands = Vec::new();
+        if !self.sysroot.trim().is_empty() {
+            commands.push(format!("-gdb-set sysroot {}", self.sysroot.trim()));
+        }
+        if !self.solib_search_path.trim().is_empty() {
+            commands.push(format!(
+                "-gdb-set solib-search-path {}",
+                self.solib_search_path.trim()
+            ));
+        }
+        commands
+    }
+
+    /// The command connecting to the gdbserver
+    pub fn connect_command(&self) -> String {
+        let kind = if self.extended {
+            "extended-remote"
+        } else {
+            "remote"
+        };
+        format!("-target-select {kind} {}", self.address.trim())
+    }
+}
+
+/// State of the connection to a gdbserver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionState {
+    Connecting,
+    Connected,
+    Disconnected {
+        error: Option<String>,
+        /// When to reconnect, `None` when reconnecting is off
+        retry_at: Option<Instant>,
+    },
+}
+
+/// The gdbserver GDB is connected to
+#[derive(Debug, Clone)]
+pub struct RemoteConnection {
+    target: RemoteTarget,
+    state: ConnectionState,
+}
+
+impl RemoteConnection {
+    pub fn new(target: RemoteTarget) -> Self {
+        Self {
+            target,
+            state: ConnectionState::Connecting,
+        }
+    }
+
+    pub fn target(&self) -> &RemoteTarget {
+        &self.target
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.state == ConnectionState::Connected
+    }
+
+    /// Whether a reconnect is scheduled, which needs repaints to happen on time
+    pub fn is_waiting(&self) -> bool {
+        matches!(
+            self.state,
+            ConnectionState::Disconnected {
+                retry_at: Some(_),
+                ..
+            }
+        )
+    }
+
+    pub fn connected(&mut self) {
+        self.state = ConnectionState::Connected;
+    }
+
+    /// The connection failed or was closed, scheduling a reconnect if enabled
+    pub fn disconnected(&mut self, error: Option<String>) {
+        self.state = ConnectionState::Disconnected {
+            error,
+            retry_at: self.target.reconnect.then(|| Instant::now() + RETRY_DELAY),
+        };
+    }
+
+    /// Stop reconnecting, e.g. as the gdbserver is gone for good
+    pub fn disable_reconnect(&mut self) {
+        self.target.reconnect = false;
+        if let ConnectionState::Disconnected { retry_at, .. } = &mut self.state {
+            *retry_at = None;
+        }
+    }
+
+    /// Start connecting again, returning false when already connecting
+    pub fn reconnect(&mut self) -> bool {
+        if self.state == ConnectionState::Connecting {
+            return false;
+        }
+        self.state = ConnectionState::Connecting;
+        true
+    }
+
+    /// Start connecting again once the scheduled reconnect is due
+    pub fn poll(&mut self) -> bool {
+        match self.state {
+            ConnectionState::Disconnected {
+                retry_at: Some(retry_at),
+                ..
+            } if Instant::now() >= retry_at => self.reconnect(),
+            _ => false,
+        }
+    }
+
+    /// Render the connection state for the menu bar, returning true to reconnect
+    pub fn ui(&self, ui: &mut Ui) -> bool {
+        let address = self.target.address.trim();
+        match &self.state {
+            ConnectionState::Connecting => {
+                ui.spinner();
+                ui.weak(format!("Connecting to {address}"));
+                false
+            }
+            ConnectionState::Connected => {
+                ui.colored_label(Color32::from_rgb(80, 200, 80), format!("Remote {address}"));
+                false
+            }
+            ConnectionState::Disconnected { error, retry_at } => {
+                let text = match retry_at {
+                    Some(_) => format!("Lost {address}, reconnecting"),
+                    None => format!("Disconnected from {address}"),
+                };
+                let label = ui.colored_label(ui.visuals().error_fg_color, text);
+                if let Some(error) = error {
+                    label.on_hover_text(error);
+                }
+                ui.button("Reconnect").clicked()
+            }
+        }
+    }
+}
+
+/// State of the connect window
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ConnectDialog {
+    /// The last target, kept to connect to it again
+    target: RemoteTarget,
+}
+
+impl ConnectDialog {
+    /// Render the window contents, returning the target to connect to
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<RemoteTarget> {
+        let mut connect = None;
+
+        egui::Grid::new("connect")
+            .num_columns(2)
+            .spacing([8.0, 6.0])
+            .show(ui, |ui| {
+                ui.label("Target");
+                ui.add(
+                    TextEdit::singleline(&mut self.target.address)
+                        .hint_text("host:port or /dev/ttyUSB0")
+                        .font(egui::TextStyle::Monospace),
+                );
+                ui.end_row();
+
+                ui.label("Sysroot");
+                ui.add(
+                    TextEdit::singleline(&mut self.target.sysroot)
+                        .hint_text("target:")
+                        .font(egui::TextStyle::Monospace),
+                );
+                ui.end_row();
+
+                ui.label("Library path");
+                ui.add(
+                    TextEdit::singleline(&mut self.target.solib_search_path)
+                        .hint_text("solib-search-path")
+                        .font(egui::TextStyle::Monospace),
+                );
+                ui.end_row();
+            });
+
+        ui.checkbox(&mut self.target.extended, "Extended remote")
+            .on_hover_text("Keep the connection when the program exits, allowing run and attach");
+        ui.checkbox(
+            &mut self.target.reconnect,
+            "Reconnect when the connection is lost",
+        );
+
+        ui.separator();
+
+        if ui
+            .add_enabled(
+                !self.target.address.trim().is_empty(),
+                egui::Button::new("Connect"),
+            )
+            .clicked()
+        {
+            connect = Some(self.target.clone());
+        }
+
+        connect
+    }
+}
@@ -0,0 +1,269 @@
+use crate::launch::shell_quote;
+use crate::remote::{CONNECT_TIMEOUT, RemoteTarget};
+use nix::sys::signal::{self, Signal};
+use std::error::Error;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::time::SystemTime;
+
+/// Where in a trace the replay starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayTarget {
+    /// Debug the process with this recorded pid, from when it was started
+    Process(u32),
+    /// Start at this event number
+    Event(u64),
+}
+
+impl ReplayTarget {
+    fn args(self) -> [String; 2] {
+        match self {
+            Self::Process(pid) => ["-p".to_owned(), pid.to_string()],
+            Self::Event(event) => ["-g".to_owned(), event.to_string()],
+        }
+    }
+}
+
+/// How often rr is started again when it exits before GDB connected, e.g. as its port was taken
+const MAX_RESTARTS: u32 = 3;
+
+/// What happened to an `rr replay` since it was last polled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayEvent {
+    /// Started again on another port, GDB has to connect to the new target
+    Restarted,
+    /// Exited for good
+    Exited(ExitStatus),
+}
+
+/// An `rr replay` process serving a recorded trace to GDB over TCP
+#[derive(Debug)]
+pub struct RrReplay {
+    process: Child,
+    port: u16,
+    trace: Option<PathBuf>,
+    targets: Vec<ReplayTarget>,
+    restarts: u32,
+    exited: bool,
+}
+
+impl RrReplay {
+    /// Replay a trace, the latest one when `trace` is `None`
+    pub fn spawn(trace: Option<&Path>, targets: &[ReplayTarget]) -> Result<Self, Box<dyn Error>> {
+        let (process, port) = start(trace, targets)?;
+        Ok(Self {
+            process,
+            port,
+            trace: trace.map(Path::to_path_buf),
+            targets: targets.to_vec(),
+            restarts: 0,
+            exited: false,
+        })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Arguments for the GDB connecting to the replay
+    pub fn gdb_args(&self) -> Vec<String> {
+        // rr may take a while to start serving a large trace, GDB keeps trying meanwhile
+        vec![
+            "-iex".to_owned(),
+            format!("set tcp connect-timeout {}", CONNECT_TIMEOUT.as_secs() / 2),
+        ]
+    }
+
+    /// The gdbserver of the replay
+    pub fn target(&self) -> RemoteTarget {
+        RemoteTarget {
+            address: format!("127.0.0.1:{}", self.port),
+            extended: true,
+            sysroot: "/".to_owned(),
+            solib_search_path: String::new(),
+            // Connecting again covers a replay taking longer to start than the connect timeout
+            reconnect: true,
+        }
+    }
+
+    /// Check whether rr exited, starting it again on a new port if GDB is not connected yet
+    ///
+    /// The port is picked before rr binds it, so another process may take it in between.
+    pub fn poll(&mut self, connected: bool) -> Option<ReplayEvent> {
+        if self.exited {
+            return None;
+        }
+        let status = self.process.try_wait().ok().flatten()?;
+        if !status.success() && !connected && self.restarts < MAX_RESTARTS {
+            self.restarts += 1;
+            if let Ok((process, port)) = start(self.trace.as_deref(), &self.targets) {
+                self.process = process;
+                self.port = port;
+                return Some(ReplayEvent::Restarted);
+            }
+        }
+        self.exited = true;
+        Some(ReplayEvent::Exited(status))
+    }
+}
+
+/// Start `rr replay` serving GDB on a free port
+fn start(trace: Option<&Path>, targets: &[ReplayTarget]) -> Result<(Child, u16), Box<dyn Error>> {
+    let port = free_port()?;
+
+    let mut command = Command::new("rr");
+    command
+        .arg("replay")
+        .arg(format!("--dbgport={port}"))
+        .stdin(Stdio::null());
+    for target in targets {
+        command.args(target.args());
+    }
+    if let Some(trace) = trace {
+        command.arg(trace);
+    }
+
+    Ok((command.spawn()?, port))
+}
+
+impl Drop for RrReplay {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+/// An `rr record` of a program running in the background
+#[derive(Debug)]
+pub struct RrRecord {
+    process: Child,
+}
+
+impl RrRecord {
+    /// Start recording an executable with its arguments
+    ///
+    /// The arguments are split by the shell, the same way GDB does for the launch arguments.
+    pub fn spawn(executable: &Path, args: &str) -> Result<Self, Box<dyn Error>> {
+        // `exec` keeps rr as the child, so it gets the interrupt stopping the recording
+        let script = format!(
+            "exec rr record {} {args}",
+            shell_quote(&executable.to_string_lossy())
+        );
+        let process = Command::new("/bin/sh")
+            .arg("-c")
+            .arg(script)
+            .stdin(Stdio::null())
+            .spawn()?;
+        Ok(Self { process })
+    }
+
+    /// The exit status of rr once the recording is finished
+    pub fn try_finish(&mut self) -> Option<ExitStatus> {
+        self.process.try_wait().ok().flatten()
+    }
+
+    /// Interrupt the recorded program, letting rr finish writing the trace
+    pub fn stop(&mut self) -> Result<(), Box<dyn Error>> {
+        let pid = nix::unistd::Pid::from_raw(self.process.id() as i32);
+        signal::kill(pid, Signal::SIGINT)?;
+        Ok(())
+    }
+}
+
+impl Drop for RrRecord {
+    fn drop(&mut self) {
+        if self.try_finish().is_none() {
+            let _ = self.stop();
+            let _ = self.process.wait();
+        }
+    }
+}
+
+/// A recorded trace directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trace {
+    pub path: PathBuf,
+    pub name: String,
+    pub modified: Option<SystemTime>,
+    /// Whether `latest-trace` points to this trace
+    pub latest: bool,
+}
+
+/// A process of a trace as listed by `rr ps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceProcess {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub exit: String,
+    pub command: String,
+}
+
+/// The directory rr saves traces in
+pub fn trace_root() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os("_RR_TRACE_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    let data = std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")))?;
+    Some(data.join("rr"))
+}
+
+/// List the traces in a trace directory, newest first
+pub fn list_traces(root: &Path) -> std::io::Result<Vec<Trace>> {
+    let latest = std::fs::canonicalize(root.join("latest-trace")).ok();
+
+    let mut traces = Vec::new();
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        // Every trace has a version file, which skips `latest-trace` and stray files
+        if name == "latest-trace" || !entry.path().join("version").is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+        traces.push(Trace {
+            latest: latest.as_deref() == std::fs::canonicalize(&path).ok().as_deref(),
+            modified: entry.metadata().and_then(|m| m.modified()).ok(),
+            name,
+            path,
+        });
+    }
+
+    traces.sort_by_key(|t| std::cmp::Reverse(t.modified));
+    Ok(traces)
+}
+
+/// List the processes of a trace with `rr ps`
+pub fn list_processes(trace: &Path) -> Result<Vec<TraceProcess>, Box<dyn Error>> {
+    let output = Command::new("rr").arg("ps").arg(trace).output()?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr)
+            .trim()
+            .to_owned()
+            .into());
+    }
+
+    // A PID PPID EXIT CMD header followed by one tab separated line per process
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.splitn(4, '\t');
+            Some(TraceProcess {
+                pid: fields.next()?.trim().parse().ok()?,
+                ppid: fields.next()?.trim().parse().ok(),
+                exit: fields.next()?.trim().to_owned(),
+                command: fields.next().unwrap_or_default().trim().to_owned(),
+            })
+        })
+        .collect())
+}
+
+/// Find a TCP port nothing is listening on
+fn free_port() -> Result<u16, Box<dyn Error>> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    Ok(listener.local_addr()?.port())
+}
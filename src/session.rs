@@ -0,0 +1,74 @@
+use crate::cli::Cli;
+use crate::remote::RemoteTarget;
+use crate::rr::ReplayTarget;
+use std::path::PathBuf;
+
+/// How GDB is started and what it debugs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Session {
+    /// Debug an executable, started from GDB
+    Launch(PathBuf),
+    /// Replay an rr trace, the latest one when `trace` is `None`
+    Replay {
+        trace: Option<PathBuf>,
+        targets: Vec<ReplayTarget>,
+    },
+    /// Attach to a running process
+    Attach(u32),
+    /// Inspect a core dump, with the executable that produced it if known
+    Core {
+        executable: Option<PathBuf>,
+        core: PathBuf,
+    },
+    /// Connect to a gdbserver, with the local copy of the executable if known
+    Remote {
+        executable: Option<PathBuf>,
+        target: RemoteTarget,
+    },
+}
+
+impl Session {
+    /// The session requested on the command line, if any
+    pub fn from_cli(cli: &Cli) -> Option<Self> {
+        if let Some(address) = &cli.remote {
+            return Some(Self::Remote {
+                executable: cli.executable.as_ref().map(PathBuf::from),
+                target: RemoteTarget {
+                    address: address.clone(),
+                    extended: cli.extended_remote,
+                    sysroot: cli.sysroot.clone().unwrap_or_default(),
+                    solib_search_path: cli.solib_search_path.clone().unwrap_or_default(),
+                    ..RemoteTarget::default()
+                },
+            });
+        }
+
+        if let Some(core) = &cli.core {
+            return Some(Self::Core {
+                executable: cli.executable.as_ref().map(PathBuf::from),
+                core: PathBuf::from(core),
+            });
+        }
+
+        if let Some(pid) = cli.attach {
+            return Some(Self::Attach(pid));
+        }
+
+        if cli.rr_replay {
+            let targets = cli
+                .rr_process
+                .map(ReplayTarget::Process)
+                .into_iter()
+                .chain(cli.rr_event.map(ReplayTarget::Event))
+                .collect();
+            return Some(Self::Replay {
+                trace: cli.rr_trace.as_ref().map(PathBuf::from),
+                targets,
+            });
+        }
+
+        cli.executable
+            .as_ref()
+            .map(|e| Self::Launch(PathBuf::from(e)))
+    }
+}
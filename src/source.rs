@@ -0,0 +1,152 @@
+use egui::Color32;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The source file shown in the Source tab
+#[derive(Debug, Default)]
+pub struct SourceView {
+    path: Option<PathBuf>,
+    code: String,
+    language: String,
+    focused_line: Option<usize>,
+    scroll_to_focus: bool,
+    /// Why the last file could not be shown
+    error: Option<String>,
+}
+
+impl SourceView {
+    /// Show the given file with `line` (1-based) focused, loading it if needed
+    ///
+    /// When the file cannot be read, the view is cleared and shows the error instead.
+    pub fn show_location(&mut self, path: &Path, line: Option<usize>) -> io::Result<()> {
+        if self.path.as_deref() != Some(path) {
+            let code = match std::fs::read_to_string(path) {
+                Ok(code) => code,
+                Err(e) => {
+                    *self = Self {
+                        error: Some(format!("Failed to load {}: {e}", path.display())),
+                        ..Self::default()
+                    };
+                    return Err(e);
+                }
+            };
+            self.code = code;
+            self.language = language_from_path(path);
+            self.path = Some(path.to_path_buf());
+            self.scroll_to_focus = true;
+            self.error = None;
+        }
+
+        if self.focused_line != line {
+            self.focused_line = line;
+            self.scroll_to_focus = true;
+        }
+
+        Ok(())
+    }
+
+    /// Forget the loaded file
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Set the focused line (1-based). Pass `None` to clear focus
+    pub fn set_focused_line(&mut self, line_number: Option<usize>) {
+        if self.focused_line != line_number {
+            self.focused_line = line_number;
+            self.scroll_to_focus = true;
+        }
+    }
+
+    pub fn focused_line(&self) -> Option<usize> {
+        self.focused_line
+    }
+
+    /// Check if the view should scroll to the focused line and reset the flag
+    pub fn take_scroll_to_focus(&mut self) -> bool {
+        std::mem::take(&mut self.scroll_to_focus)
+    }
+}
+
+/// Pick the syntax highlighting language for a file based on its extension
+pub fn language_from_path(path: &Path) -> String {
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    match extension.as_str() {
+        "c" | "h" => "c".to_owned(),
+        "cc" | "cpp" | "cxx" | "c++" | "hh" | "hpp" | "hxx" | "h++" | "inl" => "cpp".to_owned(),
+        "rs" => "rs".to_owned(),
+        "py" | "pyw" => "py".to_owned(),
+        _ => extension,
+    }
+}
+
+/// A breakpoint marker drawn in the gutter of a code view
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GutterMarker {
+    Enabled,
+    Disabled,
+    Pending,
+}
+
+impl GutterMarker {
+    pub fn color(self, ui: &egui::Ui) -> Color32 {
+        match self {
+            Self::Enabled => Color32::from_rgb(220, 50, 50),
+            Self::Disabled => ui.visuals().weak_text_color(),
+            Self::Pending => Color32::from_rgb(230, 150, 30),
+        }
+    }
+
+    /// The most important of two markers on the same line
+    pub fn max(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Enabled, _) | (_, Self::Enabled) => Self::Enabled,
+            (Self::Pending, _) | (_, Self::Pending) => Self::Pending,
+            _ => Self::Disabled,
+        }
+    }
+}
+
+/// An action requested from the gutter of a code view, for a 1-based line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GutterAction {
+    /// Insert a breakpoint, or delete the ones on the line
+    Toggle(usize),
+    Conditional(usize, String),
+    Temporary(usize),
+    Disabled(usize),
+    RunToCursor(usize),
+}
+
+impl GutterAction {
+    /// The line the action applies to
+    pub fn line(&self) -> usize {
+        match self {
+            Self::Toggle(line)
+            | Self::Conditional(line, _)
+            | Self::Temporary(line)
+            | Self::Disabled(line)
+            | Self::RunToCursor(line) => *line,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use crate::mi::{Tuple, Value};
+use egui::{Grid, RichText, ScrollArea, Ui};
+
+/// Number of frames fetched per `-stack-list-frames` request
+pub const PAGE_SIZE: usize = 64;
+
+/// A stack frame as reported by GDB
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub level: usize,
+    pub function: String,
+    pub file: Option<String>,
+    pub fullname: Option<String>,
+    pub line: Option<usize>,
+    pub address: String,
+    pub library: Option<String>,
+}
+
+impl Frame {
+    /// Build a frame from a `frame` tuple
+    pub fn from_tuple(frame: &Tuple) -> Option<Self> {
+        Some(Self {
+            level: frame.get_str("level")?.parse().ok()?,
+            function: frame.get_str("func").unwrap_or("??").to_owned(),
+            file: frame.get_str("file").map(str::to_owned),
+            fullname: frame.get_str("fullname").map(str::to_owned),
+            line: frame.get_str("line").and_then(|l| l.parse().ok()),
+            address: frame.get_str("addr").unwrap_or_default().to_owned(),
+            library: frame.get_str("from").map(str::to_owned),
+        })
+    }
+
+    /// Human readable `file:line` location, or the library for frames without debug info
+    pub fn location(&self) -> String {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => format!("{file}:{line}"),
+            (Some(file), None) => file.clone(),
+            _ => self.library.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// An action requested from the Stack tab
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StackAction {
+    /// Select the frame with the given level
+    Select(usize),
+    /// Fetch frames `low..=high`
+    Fetch { low: usize, high: usize },
+}
+
+/// State of the Stack tab
+#[derive(Debug, Default)]
+pub struct Stack {
+    frames: Vec<Frame>,
+    selected: usize,
+    complete: bool,
+    fetching: bool,
+}
+
+impl Stack {
+    /// Forget all frames, returning the request for the first page
+    pub fn reset(&mut self) -> StackAction {
+        self.frames.clear();
+        self.selected = 0;
+        self.complete = false;
+        self.fetching = true;
+        StackAction::Fetch {
+            low: 0,
+            high: PAGE_SIZE - 1,
+        }
+    }
+
+    /// Forget all frames without fetching new ones, e.g. when the inferior exited
+    pub fn clear(&mut self) {
+        *self = Self {
+            complete: true,
+            ..Self::default()
+        };
+    }
+
+    /// Append a page of frames from the result of `-stack-list-frames`
+    pub fn add_page(&mut self, stack: &[Value]) {
+        let frames: Vec<Frame> = stack
+            .iter()
+            .filter_map(|f| f.as_tuple().and_then(Frame::from_tuple))
+            .collect();
+
+        self.complete = frames.len() < PAGE_SIZE;
+        self.fetching = false;
+        for frame in frames {
+            if self
+                .frames
+                .last()
+                .is_none_or(|last| last.level < frame.level)
+            {
+                self.frames.push(frame);
+            }
+        }
+    }
+
+    /// Stop waiting for a page that failed to load
+    pub fn fetch_failed(&mut self) {
+        self.fetching = false;
+        self.complete = true;
+    }
+
+    pub fn select(&mut self, level: usize) {
+        self.selected = level;
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn frame(&self, level: usize) -> Option<&Frame> {
+        self.frames.iter().find(|f| f.level == level)
+    }
+
+    /// Render the frame list, returning the requested action
+    pub fn ui(&mut self, ui: &mut Ui) -> Option<StackAction> {
+        let mut action = None;
+
+        ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+            Grid::new("stack")
+                .striped(true)
+                .num_columns(5)
+                .show(ui, |ui| {
+                    for header in ["Level", "Function", "Location", "Address", "Library"] {
+                        ui.label(RichText::new(header).strong());
+                    }
+                    ui.end_row();
+
+                    for frame in &self.frames {
+                        let selected = frame.level == self.selected;
+                        if ui
+                            .selectable_label(
+                                selected,
+                                RichText::new(frame.level.to_string()).monospace(),
+                            )
+                            .clicked()
+                            | ui.selectable_label(
+                                selected,
+                                RichText::new(&frame.function).monospace(),
+                            )
+                            .clicked()
+                        {
+                            action = Some(StackAction::Select(frame.level));
+                        }
+
+                        let location = ui.monospace(frame.location());
+                        if let Some(fullname) = &frame.fullname {
+                            location.on_hover_text(fullname);
+                        }
+                        ui.monospace(&frame.address);
+                        ui.label(frame.library.as_deref().unwrap_or(""));
+                        ui.end_row();
+                    }
+                });
+
+            if !self.complete {
+                // Fetch the next page once the end of the list scrolls into view
+                let response = ui.weak("Loading more frames...");
+                if !self.fetching && ui.is_rect_visible(response.rect) {
+                    self.fetching = true;
+                    let low = self.frames.last().map_or(0, |f| f.level + 1);
+                    action = Some(StackAction::Fetch {
+                        low,
+                        high: low + PAGE_SIZE - 1,
+                    });
+                }
+            }
+        });
+
+        action
+    }
+}
@@ -1,8 +1,32 @@
-use crate::gdb::Gdb;
-use egui::{Color32, Key, RichText, ScrollArea, TextEdit, TextStyle, Ui, WidgetText};
+use crate::breakpoints::Breakpoints;
+use crate::controls::ExecCommand;
+use crate::disassembly::{Disassembly, DisassemblyLine, FALLBACK_RANGE};
+use crate::gdb::{DEFAULT_REQUEST_TIMEOUT, Gdb, GdbOutput, Request};
+use crate::history::History;
+use crate::inferior::InferiorState;
+use crate::memory::{Memory, MemoryAction};
+use crate::mi::{
+    AsyncKind, AsyncRecord, Record, ResultRecord, StreamKind, StreamRecord, Tuple, Value,
+    parse_address, quote,
+};
+use crate::output::Output;
+use crate::registers::{RegisterAction, Registers};
+use crate::remote::{self, RemoteConnection, RemoteTarget};
+use crate::source::{GutterAction, GutterMarker, SourceView};
+use crate::stack::{Stack, StackAction};
+use crate::threads::Threads;
+use crate::variables::{DisplayFormat, VarAction, VarObj, VarTree, WatchExpression};
+use egui::text::{CCursor, CCursorRange};
+use egui::{
+    Color32, CursorIcon, Id, Key, Label, Modifiers, RichText, ScrollArea, Sense, TextEdit,
+    TextStyle, Ui, WidgetText,
+};
 use egui_dock::TabViewer;
 use egui_extras::syntax_highlighting::{CodeTheme, highlight};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
 
 /// Different types of log entries with associated colors
 #[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
@@ -17,6 +41,12 @@ pub enum LogType {
     Info,
     /// Warning
     Warning,
+    /// Output of the debugged program
+    Target,
+    /// GDB's log stream, echoed commands and diagnostics
+    Log,
+    /// Async notification from GDB
+    Notification,
 }
 
 impl LogType {
@@ -52,6 +82,27 @@ impl LogType {
                     Color32::from_rgb(200, 150, 0)
                 }
             }
+            Self::Target => {
+                if is_dark {
+                    Color32::from_rgb(100, 220, 220)
+                } else {
+                    Color32::from_rgb(0, 130, 130)
+                }
+            }
+            Self::Log => {
+                if is_dark {
+                    Color32::from_rgb(210, 180, 130)
+                } else {
+                    Color32::from_rgb(140, 100, 40)
+                }
+            }
+            Self::Notification => {
+                if is_dark {
+                    Color32::from_rgb(160, 160, 160)
+                } else {
+                    Color32::from_rgb(110, 110, 110)
+                }
+            }
         }
     }
 }
@@ -102,6 +153,14 @@ impl LogEntry {
         Self::new(content, LogType::Warning)
     }
 
+    pub fn target(content: String) -> Self {
+        Self::new(content, LogType::Target)
+    }
+
+    pub fn notification(content: String) -> Self {
+        Self::new(content, LogType::Notification)
+    }
+
     /// Format the log entry for display with colors
     pub fn format_for_display(&self, ui: &egui::Ui) -> RichText {
         let formatted = format!("{} {}", self.timestamp, self.content);
@@ -129,9 +188,31 @@ pub enum Tab {
     Locals,
     Registers,
     Data,
+    Disassembly,
+    Output,
 }
 
 impl Tab {
+    /// Every tab, in the order of the Windows menu
+    pub const ALL: [Self; 16] = [
+        Self::Source,
+        Self::Console,
+        Self::Exe,
+        Self::Breakpoints,
+        Self::Commands,
+        Self::Struct,
+        Self::Stack,
+        Self::Files,
+        Self::Thread,
+        Self::CmdSearch,
+        Self::Watch,
+        Self::Locals,
+        Self::Registers,
+        Self::Data,
+        Self::Disassembly,
+        Self::Output,
+    ];
+
     pub fn title(&self) -> &'static str {
         match self {
             Self::Source => "Source",
@@ -148,6 +229,132 @@ impl Tab {
             Self::Locals => "Locals",
             Self::Registers => "Registers",
             Self::Data => "Data",
+            Self::Disassembly => "Disassembly",
+            Self::Output => "Output",
+        }
+    }
+}
+
+/// A request queued by a tab and sent to GDB on the next update
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Query {
+    /// Refresh the breakpoint table
+    BreakList,
+    /// Modify breakpoints, refreshing the table afterwards
+    Breakpoint(String),
+    /// A command whose only interesting result is an error
+    Action(String),
+    /// Fetch a page of stack frames
+    StackFrames { low: usize, high: usize },
+    /// Select a stack frame by level
+    SelectFrame(usize),
+    /// List the locals and arguments of the selected frame
+    ListLocals(u64),
+    /// Create a variable object for a local of the selected frame
+    CreateLocal { generation: u64, expression: String },
+    /// Fetch the children of a variable object
+    VarChildren(String),
+    /// Update all variable objects
+    VarUpdate,
+    /// Assign a new value to a variable object
+    VarAssign { name: String, value: String },
+    /// Create a floating variable object for a watch expression
+    CreateWatch {
+        expression: String,
+        format: DisplayFormat,
+    },
+    /// Change the display format of a variable object
+    VarSetFormat { name: String, format: &'static str },
+    /// Fetch the register names of the target
+    RegisterNames,
+    /// Fetch all register values in the given format
+    RegisterValues(char),
+    /// Fetch the registers changed since the last stop
+    ChangedRegisters,
+    /// Assign a new value to a register
+    RegisterAssign { name: String, value: String },
+    /// Fetch all threads
+    ThreadInfo,
+    /// Switch to another thread
+    SelectThread(String),
+    /// Read memory for the Data tab
+    ReadMemory { address: String, count: usize },
+    /// Write memory edited in the Data tab
+    WriteMemory { address: u64, bytes: Vec<u8> },
+    /// Disassemble the function around an address, or a fixed range if it has none
+    Disassemble { address: u64, whole_function: bool },
+    /// Fetch the frame of the current thread, for targets that never send a stop record
+    CurrentFrame,
+    /// Fetch the signal that terminated a core dump
+    CrashSignal,
+    /// Connect to a gdbserver
+    Connect(String),
+}
+
+impl Query {
+    /// How long to wait for the result
+    fn timeout(&self) -> Duration {
+        match self {
+            Self::Connect(_) => remote::CONNECT_TIMEOUT,
+            _ => DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    fn command(&self) -> String {
+        match self {
+            Self::BreakList => "-break-list".to_owned(),
+            Self::Breakpoint(command) | Self::Action(command) | Self::Connect(command) => {
+                command.clone()
+            }
+            Self::StackFrames { low, high } => format!("-stack-list-frames {low} {high}"),
+            Self::SelectFrame(level) => format!("-stack-select-frame {level}"),
+            Self::ListLocals(_) => "-stack-list-variables --no-values".to_owned(),
+            Self::CreateLocal { expression, .. } => {
+                format!("-var-create - * {}", quote(expression))
+            }
+            Self::VarChildren(name) => format!("-var-list-children --all-values {name}"),
+            Self::VarUpdate => "-var-update --all-values *".to_owned(),
+            Self::VarAssign { name, value } => format!("-var-assign {name} {}", quote(value)),
+            Self::CreateWatch { expression, format } => {
+                format!(
+                    "-var-create - @ {}",
+                    quote(&format.wrap_expression(expression))
+                )
+            }
+            Self::VarSetFormat { name, format } => format!("-var-set-format {name} {format}"),
+            Self::RegisterNames => "-data-list-register-names".to_owned(),
+            Self::RegisterValues(format) => {
+                format!("-data-list-register-values --skip-unavailable {format}")
+            }
+            Self::ChangedRegisters => "-data-list-changed-registers".to_owned(),
+            Self::ThreadInfo => "-thread-info".to_owned(),
+            Self::CurrentFrame => "-stack-info-frame".to_owned(),
+            Self::CrashSignal => "-data-evaluate-expression $_exitsignal".to_owned(),
+            Self::SelectThread(id) => format!("-thread-select {id}"),
+            Self::ReadMemory { address, count } => {
+                format!("-data-read-memory-bytes {} {count}", quote(address))
+            }
+            Self::WriteMemory { address, bytes } => {
+                let contents: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+                format!("-data-write-memory-bytes 0x{address:x} {contents}")
+            }
+            Self::Disassemble {
+                address,
+                whole_function: true,
+            } => format!("-data-disassemble -a 0x{address:x} -- 4"),
+            Self::Disassemble {
+                address,
+                whole_function: false,
+            } => format!(
+                "-data-disassemble -s 0x{address:x} -e 0x{:x} -- 4",
+                address.saturating_add(FALLBACK_RANGE)
+            ),
+            Self::RegisterAssign { name, value } => {
+                format!(
+                    "-data-evaluate-expression {}",
+                    quote(&format!("${name} = {value}"))
+                )
+            }
         }
     }
 }
@@ -155,12 +362,16 @@ impl Tab {
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Tabs {
     scroll_lock: bool,
+    /// Watch expressions, keyed by executable path
+    #[serde(default)]
+    watches: BTreeMap<String, Vec<WatchExpression>>,
+    /// Commands entered in the Console
+    #[serde(default)]
+    history: History,
 
     #[serde(skip)]
     console_input: String,
     #[serde(skip)]
-    console_input_prev: String,
-    #[serde(skip)]
     logs: Vec<LogEntry>,
     #[serde(skip)]
     gdb_available: bool,
@@ -169,30 +380,779 @@ pub struct Tabs {
     #[serde(skip)]
     last_log_count: usize,
     #[serde(skip)]
-    focused_line: Option<usize>,
+    source: SourceView,
+    #[serde(skip)]
+    breakpoints: Breakpoints,
+    #[serde(skip)]
+    stack: Stack,
+    #[serde(skip)]
+    locals: VarTree,
+    /// Identifies the frame the locals were created in
+    #[serde(skip)]
+    locals_frame: Option<String>,
+    /// Incremented whenever the locals are recreated, to drop stale results
+    #[serde(skip)]
+    locals_generation: u64,
+    #[serde(skip)]
+    watch_tree: VarTree,
+    #[serde(skip)]
+    new_watch: String,
+    #[serde(skip)]
+    executable: Option<String>,
+    #[serde(skip)]
+    registers: Registers,
+    #[serde(skip)]
+    threads: Threads,
+    #[serde(skip)]
+    memory: Memory,
+    #[serde(skip)]
+    disassembly: Disassembly,
+    #[serde(skip)]
+    inferior: InferiorState,
+    #[serde(skip)]
+    remote: Option<RemoteConnection>,
+    #[serde(skip)]
+    output: Output,
+    #[serde(skip)]
+    queries: Vec<Query>,
+    #[serde(skip)]
+    in_flight: Vec<(Query, Request)>,
 }
 
 impl Tabs {
     /// Set the focused line (1-based). Pass `None` to clear focus
     pub fn set_focused_line(&mut self, line_number: Option<usize>) {
-        self.focused_line = line_number;
+        self.source.set_focused_line(line_number);
+    }
+
+    /// Show the location of a frame tuple in the Source tab
+    fn show_frame_source(&mut self, frame: &Tuple) {
+        let path = frame.get_str("fullname").or_else(|| frame.get_str("file"));
+        let line = frame.get_str("line").and_then(|l| l.parse().ok());
+        self.show_source(path.map(str::to_owned), line);
     }
 
-    /// Update logs with GDB output
+    /// Show a source location in the Source tab
+    fn show_source(&mut self, path: Option<String>, line: Option<usize>) {
+        let Some(path) = path else {
+            self.source.set_focused_line(None);
+            return;
+        };
+
+        if let Err(e) = self.source.show_location(Path::new(&path), line) {
+            self.add_warning(format!("Failed to load source {path}: {e}"));
+        }
+    }
+
+    /// Highlight an instruction in the Disassembly tab, disassembling around it if needed
+    fn show_disassembly(&mut self, address: Option<&str>) {
+        let Some(address) = address.and_then(parse_address) else {
+            return;
+        };
+        if !self.disassembly.set_pc(address) {
+            self.query(Query::Disassemble {
+                address,
+                whole_function: true,
+            });
+        }
+    }
+
+    /// Update tabs with GDB output and send queued queries
     pub fn update_from_gdb(&mut self, gdb: &Gdb) {
         while let Some(output) = gdb.try_receive_output() {
-            // Determine log type based on content
-            let log_type = if output.contains("error") || output.contains("Error") {
-                LogType::Error
-            } else if output.contains("warning") || output.contains("Warning") {
-                LogType::Warning
-            } else if output.contains("info") || output.contains("Info") {
-                LogType::Info
-            } else {
-                LogType::Output
+            let record = match output {
+                GdbOutput::Mi(record) => record,
+                GdbOutput::Stderr(line) => {
+                    self.add_error(line);
+                    continue;
+                }
             };
+            match record {
+                Record::Stream(stream) => {
+                    if remote::is_connection_lost(&stream.text) {
+                        self.on_remote_lost(None);
+                    }
+                    self.add_stream_log(&stream);
+                }
+                Record::Result(result) => {
+                    if let Some(message) = result.error_message() {
+                        if remote::is_connection_lost(message) {
+                            self.on_remote_lost(Some(message.to_owned()));
+                        }
+                        self.add_error(message.to_owned());
+                    }
+                }
+                Record::Async(record) => self.handle_async(&record),
+                // Not MI, so written by the program to the terminal it shares with GDB
+                Record::Other(line) => self.add_log_entry(LogEntry::target(line)),
+                Record::Prompt => {}
+            }
+        }
+
+        self.output.poll();
+
+        if self.remote.as_mut().is_some_and(RemoteConnection::poll) {
+            self.query_connect();
+        }
+
+        self.process_queries(gdb);
+    }
+
+    /// Update tabs from an async record
+    fn handle_async(&mut self, record: &AsyncRecord) {
+        // Execution state changes are logged as the inferior state instead
+        if record.kind != AsyncKind::Exec {
+            self.add_log_entry(LogEntry::notification(notification_text(record)));
+        }
+
+        match (record.kind, record.class.as_str()) {
+            (AsyncKind::Exec, class) => {
+                if class == "running"
+                    && let Some(thread) = record.results.get_str("thread-id")
+                {
+                    self.threads.set_running(thread);
+                }
+
+                if class == "stopped" {
+                    if let Some(thread) = record.results.get_str("thread-id") {
+                        self.threads.set_current(thread);
+                    }
+                    self.query(Query::ThreadInfo);
+
+                    match record.results.get_tuple("frame") {
+                        Some(frame) => {
+                            self.show_frame_source(frame);
+                            self.on_stopped(frame);
+                        }
+                        None => self.stack.clear(),
+                    }
+                }
+
+                match class {
+                    "running" => self.inferior = InferiorState::Running,
+                    "stopped" => self.inferior = InferiorState::from_stopped(&record.results),
+                    _ => {}
+                }
+                self.add_info(self.inferior.description());
+            }
+            (AsyncKind::Notify, "thread-group-exited") => {
+                self.inferior = InferiorState::from_thread_group_exited(&record.results);
+            }
+            (AsyncKind::Notify, "breakpoint-created" | "breakpoint-modified") => {
+                if let Some(bkpt) = record.results.get_tuple("bkpt") {
+                    self.breakpoints.upsert(bkpt);
+                }
+            }
+            (AsyncKind::Notify, "breakpoint-deleted") => {
+                if let Some(id) = record.results.get_str("id") {
+                    self.breakpoints.remove(id);
+                }
+            }
+            (AsyncKind::Notify, "thread-created") => {
+                if let Some(id) = record.results.get_str("id") {
+                    self.threads.add(id);
+                    self.query(Query::ThreadInfo);
+                }
+            }
+            (AsyncKind::Notify, "thread-exited") => {
+                if let Some(id) = record.results.get_str("id") {
+                    self.threads.remove(id);
+                }
+            }
+            (AsyncKind::Notify, "thread-selected") => {
+                if let Some(id) = record.results.get_str("id") {
+                    self.on_thread_selected(id, record.results.get_tuple("frame"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Refresh the state of all tabs after the inferior stopped
+    fn on_stopped(&mut self, frame: &Tuple) {
+        self.locals.clear_changed();
+        self.watch_tree.clear_changed();
+
+        if !self.registers.has_names() {
+            self.query(Query::RegisterNames);
+        }
+        self.query(Query::ChangedRegisters);
+        let read = self.memory.on_stopped();
+        self.handle_memory_action(read);
+
+        self.refresh_frame_views(frame);
+    }
+
+    /// Refresh the views that depend on the top frame of the current thread
+    fn refresh_frame_views(&mut self, frame: &Tuple) {
+        if let Some(arch) = frame.get_str("arch") {
+            self.registers.set_arch(arch);
+        }
+        let fetch = self.stack.reset();
+        self.handle_stack_action(fetch);
+        self.show_disassembly(frame.get_str("addr"));
+
+        let function = frame.get_str("func").unwrap_or_default();
+        self.refresh_locals(self.frame_key(0, function));
+        self.create_watches();
+        self.query(Query::VarUpdate);
+        self.refresh_registers();
+    }
+
+    /// Identifies a frame of the current thread, to know when locals must be recreated
+    fn frame_key(&self, level: usize, function: &str) -> String {
+        let thread = self.threads.current().unwrap_or_default();
+        format!("{thread}:{level}:{function}")
+    }
+
+    /// Retarget the views after switching to another thread
+    fn on_thread_selected(&mut self, id: &str, frame: Option<&Tuple>) {
+        self.threads.set_current(id);
+        if let Some(frame) = frame {
+            self.show_frame_source(frame);
+            self.refresh_frame_views(frame);
+        }
+    }
+
+    fn refresh_registers(&mut self) {
+        self.query(Query::RegisterValues(self.registers.format().mi_format()));
+    }
+
+    fn handle_register_action(&mut self, action: RegisterAction) {
+        match action {
+            RegisterAction::Refresh => self.refresh_registers(),
+            RegisterAction::Assign(name, value) => {
+                self.query(Query::RegisterAssign { name, value });
+            }
+        }
+    }
+
+    fn handle_memory_action(&mut self, action: Option<MemoryAction>) {
+        match action {
+            Some(MemoryAction::Read { address, count }) => {
+                self.query(Query::ReadMemory { address, count });
+            }
+            Some(MemoryAction::Write { address, bytes }) => {
+                self.query(Query::WriteMemory { address, bytes });
+            }
+            None => {}
+        }
+    }
+
+    /// Refresh the views that depend on the selected frame
+    fn on_frame_selected(&mut self) {
+        if let Some(frame) = self.stack.frame(self.stack.selected()) {
+            let (path, line) = (frame.fullname.clone().or(frame.file.clone()), frame.line);
+            let frame_key = self.frame_key(frame.level, &frame.function);
+            let address = frame.address.clone();
+            self.show_source(path, line);
+            self.show_disassembly(Some(&address));
+            self.refresh_locals(frame_key);
+            self.refresh_registers();
+        }
+    }
+
+    /// Recreate the locals if they belong to a different frame
+    fn refresh_locals(&mut self, frame_key: String) {
+        if self.locals_frame.as_ref() != Some(&frame_key) {
+            self.locals_frame = Some(frame_key);
+            self.reload_locals();
+        }
+    }
+
+    /// Delete the variable objects of the locals and list them again
+    fn reload_locals(&mut self) {
+        for name in self.locals.clear() {
+            self.query(Query::Action(format!("-var-delete {name}")));
+        }
+        self.locals_generation += 1;
+        self.query(Query::ListLocals(self.locals_generation));
+    }
+
+    fn handle_var_action(&mut self, action: VarAction) {
+        match action {
+            VarAction::Expand(name) => self.query(Query::VarChildren(name)),
+            VarAction::Assign(name, value) => self.query(Query::VarAssign { name, value }),
+            VarAction::Remove(expression) => self.remove_watch(&expression),
+            VarAction::SetFormat(expression, format) => self.set_watch_format(&expression, format),
+        }
+    }
+
+    /// Set the executable whose watch expressions are shown
+    pub fn set_executable(&mut self, path: &Path) {
+        self.executable = Some(path.display().to_string());
+        self.reset_watches();
+    }
+
+    /// Replace the watch expressions of an executable, keeping the formats of known ones
+    pub fn set_watches(&mut self, executable: &str, expressions: &[String]) {
+        let list = self.watches.entry(executable.to_owned()).or_default();
+        *list = expressions
+            .iter()
+            .map(|expression| {
+                list.iter()
+                    .find(|w| &w.expression == expression)
+                    .cloned()
+                    .unwrap_or_else(|| WatchExpression {
+                        expression: expression.clone(),
+                        format: DisplayFormat::default(),
+                    })
+            })
+            .collect();
+    }
+
+    /// Insert a breakpoint once GDB is available
+    pub fn insert_breakpoint(&mut self, location: &str) {
+        self.query(Query::Breakpoint(format!(
+            "-break-insert {}",
+            quote(location)
+        )));
+    }
+
+    /// The persisted watch expressions of the current executable
+    fn watch_list_mut(&mut self) -> &mut Vec<WatchExpression> {
+        let key = self.executable.clone().unwrap_or_default();
+        self.watches.entry(key).or_default()
+    }
+
+    /// Replace the watch tree with unevaluated placeholders for the persisted expressions
+    fn reset_watches(&mut self) {
+        self.watch_tree.clear();
+        for watch in self.watch_list_mut().clone() {
+            self.watch_tree.add_root(VarObj::placeholder(
+                &watch.expression,
+                "<not evaluated>",
+                watch.format,
+            ));
+        }
+    }
+
+    /// Create variable objects for the watches that do not have one yet
+    fn create_watches(&mut self) {
+        let pending: Vec<(String, DisplayFormat)> = self
+            .watch_tree
+            .roots()
+            .iter()
+            .filter(|r| r.is_placeholder())
+            .map(|r| (r.expression.clone(), r.format))
+            .collect();
+        for (expression, format) in pending {
+            self.query(Query::CreateWatch { expression, format });
+        }
+    }
+
+    fn add_watch(&mut self, expression: String) {
+        if self
+            .watch_list_mut()
+            .iter()
+            .any(|w| w.expression == expression)
+        {
+            return;
+        }
+
+        let format = DisplayFormat::default();
+        self.watch_list_mut().push(WatchExpression {
+            expression: expression.clone(),
+            format,
+        });
+        self.watch_tree
+            .add_root(VarObj::placeholder(&expression, "<not evaluated>", format));
+        if self.gdb_available {
+            self.query(Query::CreateWatch { expression, format });
+        }
+    }
+
+    fn remove_watch(&mut self, expression: &str) {
+        self.watch_list_mut().retain(|w| w.expression != expression);
+        if let Some(var) = self.watch_tree.remove_expression(expression)
+            && !var.is_placeholder()
+        {
+            self.query(Query::Action(format!("-var-delete {}", var.name)));
+        }
+    }
+
+    fn set_watch_format(&mut self, expression: &str, format: DisplayFormat) {
+        if let Some(watch) = self
+            .watch_list_mut()
+            .iter_mut()
+            .find(|w| w.expression == expression)
+        {
+            watch.format = format;
+        }
+
+        let Some(var) = self.watch_tree.root_by_expression_mut(expression) else {
+            return;
+        };
+        let old_format = std::mem::replace(&mut var.format, format);
+
+        match (
+            var.is_placeholder(),
+            old_format.mi_format(),
+            format.mi_format(),
+        ) {
+            (false, Some(_), Some(mi_format)) => {
+                let name = var.name.clone();
+                self.query(Query::VarSetFormat {
+                    name,
+                    format: mi_format,
+                });
+            }
+            // Char is displayed through a cast, so the variable object has to be recreated
+            (is_placeholder, _, _) => {
+                if !is_placeholder {
+                    let name = var.name.clone();
+                    self.query(Query::Action(format!("-var-delete {name}")));
+                }
+                self.watch_tree.replace_root(VarObj::placeholder(
+                    expression,
+                    "<not evaluated>",
+                    format,
+                ));
+                if self.gdb_available {
+                    self.query(Query::CreateWatch {
+                        expression: expression.to_owned(),
+                        format,
+                    });
+                }
+            }
+        }
+    }
+
+    fn handle_stack_action(&mut self, action: StackAction) {
+        match action {
+            StackAction::Select(level) => self.query(Query::SelectFrame(level)),
+            StackAction::Fetch { low, high } => self.query(Query::StackFrames { low, high }),
+        }
+    }
+
+    /// Queue a query to be sent to GDB on the next update
+    fn query(&mut self, query: Query) {
+        if !self.queries.contains(&query) {
+            self.queries.push(query);
+        }
+    }
+
+    /// Whether queries are waiting to be sent or for their results
+    pub fn is_busy(&self) -> bool {
+        !self.queries.is_empty() || !self.in_flight.is_empty()
+    }
+
+    /// Send queued queries and handle the results of finished ones
+    fn process_queries(&mut self, gdb: &Gdb) {
+        for query in std::mem::take(&mut self.queries) {
+            match gdb.request_with_timeout(&query.command(), query.timeout()) {
+                Ok(request) => self.in_flight.push((query, request)),
+                Err(e) => self.add_error(format!("Failed to send {}: {e}", query.command())),
+            }
+        }
+
+        for (query, request) in std::mem::take(&mut self.in_flight) {
+            match request.try_result() {
+                Ok(None) => self.in_flight.push((query, request)),
+                Ok(Some(result)) => self.handle_query_result(&query, &result),
+                Err(e) => {
+                    // Without a result the connection would be stuck connecting
+                    if let (Query::Connect(_), Some(remote)) = (&query, &mut self.remote) {
+                        remote.disconnected(Some(e.to_string()));
+                    }
+                    self.add_error(format!("{}: {e}", query.command()));
+                }
+            }
+        }
+    }
+
+    /// Update tabs from the result of a query
+    fn handle_query_result(&mut self, query: &Query, result: &ResultRecord) {
+        if let Query::CreateWatch { expression, format } = query {
+            self.handle_watch_created(expression, *format, result);
+            return;
+        }
+
+        if let Some(message) = result.error_message() {
+            match query {
+                Query::StackFrames { .. } => self.stack.fetch_failed(),
+                Query::Connect(_) => {
+                    if let Some(remote) = &mut self.remote {
+                        remote.disconnected(Some(message.to_owned()));
+                    }
+                }
+                Query::ReadMemory { .. } => {
+                    self.memory.read_failed(message);
+                    return;
+                }
+                Query::Disassemble {
+                    address,
+                    whole_function: true,
+                } => {
+                    // Code outside of any known function, e.g. without symbols
+                    self.query(Query::Disassemble {
+                        address: *address,
+                        whole_function: false,
+                    });
+                    return;
+                }
+                Query::Disassemble { .. } => {
+                    self.disassembly.fetch_failed(message);
+                    return;
+                }
+                _ => {}
+            }
+            self.add_error(message.to_owned());
+            return;
+        }
+
+        match query {
+            Query::BreakList => {
+                if let Some(table) = result.results.get_tuple("BreakpointTable") {
+                    self.breakpoints.set_from_table(table);
+                }
+            }
+            Query::Breakpoint(_) => self.query(Query::BreakList),
+            Query::RegisterNames => {
+                self.registers.set_names(
+                    result
+                        .results
+                        .get_list("register-names")
+                        .unwrap_or_default(),
+                );
+            }
+            Query::RegisterValues(_) => {
+                self.registers.set_values(
+                    result
+                        .results
+                        .get_list("register-values")
+                        .unwrap_or_default(),
+                );
+            }
+            Query::ChangedRegisters => {
+                self.registers.set_changed(
+                    result
+                        .results
+                        .get_list("changed-registers")
+                        .unwrap_or_default(),
+                );
+            }
+            Query::RegisterAssign { .. } => {
+                self.refresh_registers();
+                self.query(Query::VarUpdate);
+            }
+            Query::ThreadInfo => {
+                self.threads.set_from_info(
+                    result.results.get_list("threads").unwrap_or_default(),
+                    result.results.get_str("current-thread-id"),
+                );
+            }
+            Query::Connect(_) => {
+                if let Some(remote) = &mut self.remote {
+                    remote.connected();
+                }
+                self.query(Query::ThreadInfo);
+                self.query(Query::CurrentFrame);
+            }
+            Query::CurrentFrame => {
+                if let Some(frame) = result.results.get_tuple("frame") {
+                    self.inferior.set_frame(frame);
+                    self.show_frame_source(frame);
+                    self.on_stopped(frame);
+                }
+            }
+            Query::CrashSignal => {
+                if let Some(value) = result.results.get_str("value") {
+                    self.inferior.set_crash_signal(value);
+                }
+            }
+            Query::SelectThread(_) => {
+                if let Some(id) = result.results.get_str("new-thread-id") {
+                    self.on_thread_selected(id, result.results.get_tuple("frame"));
+                }
+            }
+            Query::ReadMemory { .. } => {
+                self.memory
+                    .set_contents(result.results.get_list("memory").unwrap_or_default());
+            }
+            Query::Disassemble { .. } => {
+                self.disassembly
+                    .set_from_result(result.results.get_list("asm_insns").unwrap_or_default());
+            }
+            Query::WriteMemory { .. } => {
+                let read = self.memory.refresh();
+                self.handle_memory_action(read);
+                self.query(Query::VarUpdate);
+            }
+            // Watch creation is handled above, including its errors
+            Query::Action(_) | Query::CreateWatch { .. } => {}
+            Query::StackFrames { .. } => {
+                self.stack
+                    .add_page(result.results.get_list("stack").unwrap_or_default());
+            }
+            Query::SelectFrame(level) => {
+                self.stack.select(*level);
+                self.on_frame_selected();
+            }
+            Query::ListLocals(generation) => {
+                if *generation != self.locals_generation {
+                    return;
+                }
+                for variable in result.results.get_list("variables").unwrap_or_default() {
+                    if let Some(name) = variable.as_tuple().and_then(|v| v.get_str("name")) {
+                        self.query(Query::CreateLocal {
+                            generation: *generation,
+                            expression: name.to_owned(),
+                        });
+                    }
+                }
+            }
+            Query::CreateLocal {
+                generation,
+                expression,
+            } => {
+                let Some(var) = VarObj::from_tuple(&result.results, Some(expression)) else {
+                    return;
+                };
+                if *generation == self.locals_generation {
+                    self.locals.add_root(var);
+                } else {
+                    self.query(Query::Action(format!("-var-delete {}", var.name)));
+                }
+            }
+            Query::VarChildren(name) => {
+                let children = result.results.get_list("children").unwrap_or_default();
+                self.locals.set_children(name, children);
+                self.watch_tree.set_children(name, children);
+            }
+            Query::VarUpdate => {
+                let changelist = result.results.get_list("changelist").unwrap_or_default();
+                self.watch_tree.apply_update(changelist);
+                let out_of_scope = self.locals.apply_update(changelist);
+                if !out_of_scope.is_empty() {
+                    // The frame the locals were created in is gone
+                    self.reload_locals();
+                }
+            }
+            Query::VarAssign { name, .. } | Query::VarSetFormat { name, .. } => {
+                if let Some(value) = result.results.get_str("value") {
+                    self.locals.set_value(name, value);
+                    self.watch_tree.set_value(name, value);
+                }
+            }
+        }
+    }
+
+    /// Replace the placeholder of a watch with its new variable object or error
+    fn handle_watch_created(
+        &mut self,
+        expression: &str,
+        format: DisplayFormat,
+        result: &ResultRecord,
+    ) {
+        let var = match result.error_message() {
+            Some(message) => VarObj::placeholder(expression, message, format),
+            None => match VarObj::from_tuple(&result.results, Some(expression)) {
+                Some(mut var) => {
+                    var.format = format;
+                    var
+                }
+                None => return,
+            },
+        };
 
-            self.logs.push(LogEntry::new(output, log_type));
+        let still_watched = self
+            .watch_tree
+            .root_by_expression_mut(expression)
+            .is_some_and(|root| root.is_placeholder() && root.format == format);
+        if still_watched {
+            if let Some(mi_format) = format.mi_format()
+                && !var.is_placeholder()
+                && format != DisplayFormat::Natural
+            {
+                self.query(Query::VarSetFormat {
+                    name: var.name.clone(),
+                    format: mi_format,
+                });
+            }
+            self.watch_tree.replace_root(var);
+        } else if !var.is_placeholder() {
+            self.query(Query::Action(format!("-var-delete {}", var.name)));
+        }
+    }
+
+    /// Issue the breakpoint commands for an action in the source gutter
+    fn handle_gutter_action(&mut self, action: GutterAction) {
+        let Some(path) = self.source.path() else {
+            return;
+        };
+        let line = action.line();
+        let location = format!("{}:{line}", path.display());
+        let existing: Vec<String> = self
+            .breakpoints
+            .at_line(path, line)
+            .map(|b| b.number.clone())
+            .collect();
+        self.apply_gutter_action(action, &location, &existing);
+    }
+
+    fn handle_disassembly_gutter_action(&mut self, action: GutterAction) {
+        let (location, existing): (String, Vec<String>) = match self.disassembly.line(action.line())
+        {
+            Some(DisassemblyLine::Instruction(insn)) => (
+                format!("*0x{:x}", insn.address),
+                self.breakpoints
+                    .at_address(insn.address)
+                    .map(|b| b.number.clone())
+                    .collect(),
+            ),
+            Some(DisassemblyLine::Source {
+                fullname: Some(fullname),
+                line,
+                ..
+            }) => (
+                format!("{fullname}:{line}"),
+                self.breakpoints
+                    .at_line(Path::new(fullname), *line)
+                    .map(|b| b.number.clone())
+                    .collect(),
+            ),
+            _ => return,
+        };
+        self.apply_gutter_action(action, &location, &existing);
+    }
+
+    /// Insert breakpoints at `location` for a gutter action, `existing` being the ones already there
+    fn apply_gutter_action(&mut self, action: GutterAction, location: &str, existing: &[String]) {
+        let location = quote(location);
+        let command = match action {
+            GutterAction::Toggle(_) => {
+                if existing.is_empty() {
+                    format!("-break-insert {location}")
+                } else {
+                    format!("-break-delete {}", existing.join(" "))
+                }
+            }
+            GutterAction::Conditional(_, condition) => {
+                format!("-break-insert -c {} {location}", quote(&condition))
+            }
+            GutterAction::Temporary(_) => format!("-break-insert -t {location}"),
+            GutterAction::Disabled(_) => format!("-break-insert -d {location}"),
+            GutterAction::RunToCursor(_) => {
+                self.query(Query::Breakpoint(format!("-break-insert -t {location}")));
+                self.query(Query::Action("-exec-continue".to_owned()));
+                return;
+            }
+        };
+
+        self.query(Query::Breakpoint(command));
+    }
+
+    /// Add the lines of a stream record, typed by the stream they were written to
+    fn add_stream_log(&mut self, stream: &StreamRecord) {
+        let log_type = match stream.kind {
+            StreamKind::Console => LogType::Output,
+            StreamKind::Target => LogType::Target,
+            StreamKind::Log => LogType::Log,
+        };
+        for line in stream.text.lines() {
+            self.logs
+                .push(LogEntry::new(line.to_owned(), log_type.clone()));
         }
     }
 
@@ -210,8 +1170,86 @@ impl Tabs {
     }
 
     /// Set GDB availability
-    pub fn set_gdb_available(&mut self, available: bool) {
+    pub fn set_gdb_available(&mut self, available: bool, ctx: &egui::Context) {
         self.gdb_available = available;
+        if available {
+            self.registers.clear();
+            self.threads.clear();
+            self.memory.clear();
+            self.disassembly.clear();
+            // Variable objects of the previous GDB are unknown to the new one
+            self.locals.clear();
+            self.locals_frame = None;
+            self.stack.clear();
+            self.source.clear();
+            self.inferior = InferiorState::NotStarted;
+            self.remote = None;
+            // The program gets its own terminal, shown in the Output tab
+            match self.output.open_tty(ctx) {
+                Ok(path) => self.query(Query::Action(format!("-inferior-tty-set {path}"))),
+                Err(e) => self.add_error(format!("Failed to open a terminal for the program: {e}")),
+            }
+            self.query(Query::BreakList);
+            self.reset_watches();
+            self.create_watches();
+        }
+    }
+
+    /// Inspect the core dump GDB was started with, which never sends a stop record
+    pub fn load_core(&mut self) {
+        self.inferior = InferiorState::post_mortem();
+        self.query(Query::ThreadInfo);
+        self.query(Query::CurrentFrame);
+        self.query(Query::CrashSignal);
+    }
+
+    /// Connect to a gdbserver, configuring GDB for it first
+    pub fn connect_remote(&mut self, target: RemoteTarget) {
+        for command in target.setup_commands() {
+            self.query(Query::Action(command));
+        }
+        self.remote = Some(RemoteConnection::new(target));
+        self.query_connect();
+    }
+
+    /// Connect to the gdbserver again after the connection was lost
+    pub fn reconnect_remote(&mut self) {
+        if self
+            .remote
+            .as_mut()
+            .is_some_and(RemoteConnection::reconnect)
+        {
+            self.query_connect();
+        }
+    }
+
+    fn query_connect(&mut self) {
+        if let Some(remote) = &self.remote {
+            self.query(Query::Connect(remote.target().connect_command()));
+        }
+    }
+
+    /// Stop reconnecting to the gdbserver
+    pub fn disable_remote_reconnect(&mut self) {
+        if let Some(remote) = &mut self.remote {
+            remote.disable_reconnect();
+        }
+    }
+
+    /// Reset the views after GDB dropped the connection to the gdbserver
+    fn on_remote_lost(&mut self, error: Option<String>) {
+        let Some(remote) = self.remote.as_mut().filter(|r| r.is_connected()) else {
+            return;
+        };
+        remote.disconnected(error);
+        self.inferior = InferiorState::NotStarted;
+        self.threads.clear();
+        self.stack.clear();
+    }
+
+    /// Get the gdbserver connection, if GDB debugs a remote target
+    pub fn remote(&self) -> Option<&RemoteConnection> {
+        self.remote.as_ref()
     }
 
     /// Get GDB availability
@@ -219,6 +1257,16 @@ impl Tabs {
         self.gdb_available
     }
 
+    /// Get the execution state of the debugged program
+    pub fn inferior(&self) -> &InferiorState {
+        &self.inferior
+    }
+
+    /// Check if an execution control command can be sent right now
+    pub fn allows(&self, command: ExecCommand) -> bool {
+        self.gdb_available && self.inferior.allows(command)
+    }
+
     /// Get pending commands and clear the queue
     pub fn take_pending_commands(&mut self) -> Vec<String> {
         std::mem::take(&mut self.pending_commands)
@@ -269,15 +1317,119 @@ impl Tabs {
     pub fn log_count(&self) -> usize {
         self.logs.len()
     }
+
+    /// Render the Console command input, with Up/Down history and Ctrl-R search
+    fn console_command_ui(&mut self, ui: &mut Ui) {
+        let id = ui.make_persistent_id("console_input");
+        let mut recalled = false;
+
+        // Taken before the text edit sees them, which would move the cursor instead
+        if ui.memory(|m| m.has_focus(id)) {
+            if ui.input_mut(|i| i.consume_key(Modifiers::CTRL, Key::R)) {
+                self.history.search_older();
+            } else if self.history.is_searching() {
+                if ui.input_mut(|i| {
+                    i.consume_key(Modifiers::NONE, Key::ArrowUp)
+                        || i.consume_key(Modifiers::NONE, Key::ArrowDown)
+                }) && let Some(command) = self.history.finish_search()
+                {
+                    self.console_input = command;
+                    recalled = true;
+                }
+            } else if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowUp)) {
+                self.history.previous(&mut self.console_input);
+                recalled = true;
+            } else if ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::ArrowDown)) {
+                self.history.next(&mut self.console_input);
+                recalled = true;
+            }
+        }
+
+        // Escape takes the focus away before any widget runs, so give it back
+        if self.history.is_searching() && ui.input(|i| i.key_pressed(Key::Escape)) {
+            self.history.cancel_search();
+            ui.memory_mut(|m| m.request_focus(id));
+        }
+
+        let searching = self.history.is_searching();
+        ui.label(if searching { "Search" } else { "Command" });
+
+        // Reserve space for the controls at the end
+        // TODO: Fix magic so it's dynamic
+        let controls_width = ui.available_width() - 105.0;
+        let text = match self.history.search_query_mut() {
+            Some(query) => query,
+            None => &mut self.console_input,
+        };
+        let response = ui.add_sized(
+            [controls_width, ui.available_height()],
+            TextEdit::singleline(text).id(id).font(TextStyle::Monospace),
+        );
+
+        if searching && response.changed() {
+            self.history.update_search();
+        }
+        if recalled {
+            move_cursor_to_end(ui, id, self.console_input.chars().count());
+        }
+
+        if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
+            response.request_focus();
+            if searching {
+                // Run the matching command
+                if let Some(command) = self.history.finish_search() {
+                    self.history.push(&command);
+                    self.add_pending_command(command);
+                }
+            } else if self.console_input.is_empty() {
+                // Repeat last command
+                if let Some(command) = self.history.last() {
+                    self.add_pending_command(command.to_owned());
+                }
+            } else {
+                // Add command to pending queue for GDB processing
+                self.history.push(&self.console_input);
+                let command = std::mem::take(&mut self.console_input);
+                self.add_pending_command(command);
+            }
+        }
+    }
+}
+
+/// Place the cursor of a text edit after its text
+fn move_cursor_to_end(ui: &Ui, id: Id, len: usize) {
+    let mut state = TextEdit::load_state(ui.ctx(), id).unwrap_or_default();
+    state
+        .cursor
+        .set_char_range(Some(CCursorRange::one(CCursor::new(len))));
+    state.store(ui.ctx(), id);
+}
+
+/// Summarize a notification as its class and plain fields, e.g. `=thread-created id="1"`
+fn notification_text(record: &AsyncRecord) -> String {
+    let prefix = match record.kind {
+        AsyncKind::Exec => '*',
+        AsyncKind::Status => '+',
+        AsyncKind::Notify => '=',
+    };
+    let mut text = format!("{prefix}{}", record.class);
+    for (name, value) in &record.results.0 {
+        if let Value::Const(value) = value {
+            text.push_str(&format!(" {name}={}", quote(value)));
+        }
+    }
+    text
 }
 
 fn code_with_line_numbers(
     ui: &mut Ui,
     code: &str,
     language: &str,
-    theme: &CodeTheme,
     focused_line: Option<usize>,
-) {
+    scroll_to_focus: bool,
+    markers: &BTreeMap<usize, GutterMarker>,
+    line_label: &dyn Fn(usize) -> String,
+) -> Option<GutterAction> {
     // HACK: Add more code for testing
     // let mut sb = String::from(code);
     // for _ in 0..100 {
@@ -285,6 +1437,9 @@ fn code_with_line_numbers(
     // }
     // let code = &sb.to_string();
 
+    let theme = &CodeTheme::from_memory(ui.ctx(), ui.style());
+    let mut action = None;
+
     egui::ScrollArea::both()
         .auto_shrink([false, false])
         .show(ui, |ui| {
@@ -295,35 +1450,97 @@ fn code_with_line_numbers(
             };
 
             for (i, line) in code.lines().enumerate() {
-                let is_focused = focused_line.is_some_and(|ln| ln == i + 1);
-
-                if is_focused {
-                    let response = egui::Frame::new()
-                        .fill(focus_fill)
-                        .show(ui, |ui| {
-                            ui.horizontal(|ui| {
-                                let line_num = format!("{:>3} ", i + 1);
-                                ui.label(RichText::new(line_num).monospace().weak());
-
-                                let highlighted =
-                                    highlight(ui.ctx(), ui.style(), theme, line, language);
-                                ui.label(highlighted);
-                            });
-                        })
-                        .response;
+                let line_number = i + 1;
+                let is_focused = focused_line.is_some_and(|ln| ln == line_number);
 
-                    ui.scroll_to_rect(response.rect, Some(egui::Align::Center));
-                } else {
-                    ui.horizontal(|ui| {
-                        let line_num = format!("{:>3} ", i + 1);
-                        ui.label(RichText::new(line_num).monospace().weak());
+                let response = egui::Frame::new()
+                    .fill(if is_focused {
+                        focus_fill
+                    } else {
+                        Color32::TRANSPARENT
+                    })
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            let marker = markers.get(&line_number).copied();
+                            let label = line_label(line_number);
+                            if let Some(gutter_action) = gutter(ui, line_number, label, marker) {
+                                action = Some(gutter_action);
+                            }
 
-                        let highlighted = highlight(ui.ctx(), ui.style(), theme, line, language);
-                        ui.label(highlighted);
-                    });
+                            let highlighted =
+                                highlight(ui.ctx(), ui.style(), theme, line, language);
+                            ui.label(highlighted);
+                        });
+                    })
+                    .response;
+
+                if is_focused && scroll_to_focus {
+                    ui.scroll_to_rect(response.rect, Some(egui::Align::Center));
                 }
             }
         });
+
+    action
+}
+
+/// Draw the breakpoint marker and number of a code line, returning the requested action
+fn gutter(
+    ui: &mut Ui,
+    line_number: usize,
+    label: String,
+    marker: Option<GutterMarker>,
+) -> Option<GutterAction> {
+    let size = ui.text_style_height(&TextStyle::Monospace);
+    let (rect, marker_response) = ui.allocate_exact_size(egui::vec2(size, size), Sense::click());
+    if let Some(marker) = marker {
+        ui.painter()
+            .circle_filled(rect.center(), size * 0.35, marker.color(ui));
+    }
+
+    let number_response =
+        ui.add(Label::new(RichText::new(label).monospace().weak()).sense(Sense::click()));
+
+    let response = (marker_response | number_response).on_hover_cursor(CursorIcon::PointingHand);
+
+    let mut action = response
+        .clicked()
+        .then_some(GutterAction::Toggle(line_number));
+
+    response.context_menu(|ui| {
+        let condition_id = ui.id().with("gutter_condition");
+        let mut condition: String = ui.data_mut(|d| d.get_temp(condition_id).unwrap_or_default());
+
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut condition)
+                    .hint_text("condition")
+                    .font(TextStyle::Monospace)
+                    .desired_width(160.0),
+            );
+            if ui.button("Conditional breakpoint").clicked() && !condition.trim().is_empty() {
+                action = Some(GutterAction::Conditional(
+                    line_number,
+                    condition.trim().to_owned(),
+                ));
+                condition.clear();
+                ui.close();
+            }
+        });
+        ui.data_mut(|d| d.insert_temp(condition_id, condition));
+
+        if ui.button("Temporary breakpoint").clicked() {
+            action = Some(GutterAction::Temporary(line_number));
+        }
+        if ui.button("Disabled breakpoint").clicked() {
+            action = Some(GutterAction::Disabled(line_number));
+        }
+        ui.separator();
+        if ui.button("Run to cursor").clicked() {
+            action = Some(GutterAction::RunToCursor(line_number));
+        }
+    });
+
+    action
 }
 
 impl TabViewer for Tabs {
@@ -336,25 +1553,93 @@ impl TabViewer for Tabs {
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
         match tab {
             Tab::Source => {
-                self.set_focused_line(Some(40));
-                let code = r#"#include <stdio.h>
+                if self.source.path().is_none() {
+                    ui.centered_and_justified(|ui| match self.source.error() {
+                        Some(error) => ui.colored_label(ui.visuals().error_fg_color, error),
+                        None => ui.weak("No source"),
+                    });
+                    return;
+                }
 
-int main(int argc, char **argv) {
-    for (size_t i = 0;; ++i) {
-        printf("%zu\n", i);
-    }
+                let scroll_to_focus = self.source.take_scroll_to_focus();
+                let markers = self
+                    .source
+                    .path()
+                    .map(|path| self.breakpoints.markers_for(path))
+                    .unwrap_or_default();
+                let action = code_with_line_numbers(
+                    ui,
+                    self.source.code(),
+                    self.source.language(),
+                    self.source.focused_line(),
+                    scroll_to_focus,
+                    &markers,
+                    &|line| format!("{line:>3} "),
+                );
+                if let Some(action) = action {
+                    self.handle_gutter_action(action);
+                }
+            }
+            Tab::Disassembly => {
+                ui.add_enabled_ui(self.inferior.is_stopped(), |ui| {
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button("Step instruction")
+                            .on_hover_text("stepi")
+                            .clicked()
+                        {
+                            self.query(Query::Action("-exec-step-instruction".to_owned()));
+                        }
+                        if ui
+                            .button("Next instruction")
+                            .on_hover_text("nexti")
+                            .clicked()
+                        {
+                            self.query(Query::Action("-exec-next-instruction".to_owned()));
+                        }
+                    });
+                });
+
+                ui.separator();
+
+                if let Some(error) = self.disassembly.error() {
+                    ui.colored_label(ui.visuals().error_fg_color, error);
+                    return;
+                }
+                if self.disassembly.is_empty() {
+                    ui.centered_and_justified(|ui| ui.weak("No disassembly"));
+                    return;
+                }
 
-    return 0;
-}"#;
-                let language = "c";
-                let theme =
-                    &egui_extras::syntax_highlighting::CodeTheme::from_memory(ui.ctx(), ui.style());
-                code_with_line_numbers(ui, code, language, theme, self.focused_line);
+                let scroll_to_pc = self.disassembly.take_scroll_to_pc();
+                let markers = self.disassembly.markers(&self.breakpoints);
+                let disassembly = &self.disassembly;
+                let action = code_with_line_numbers(
+                    ui,
+                    &disassembly.code(),
+                    "asm",
+                    disassembly.pc_row(),
+                    scroll_to_pc,
+                    &markers,
+                    &|row| disassembly.label(row),
+                );
+                if let Some(action) = action {
+                    self.handle_disassembly_gutter_action(action);
+                }
             }
             Tab::Console => {
+                // The search shows the matching command on an extra row
+                let footer_height = if self.history.is_searching() {
+                    54.0
+                } else {
+                    30.0
+                };
                 ui.vertical(|ui| {
                     ui.allocate_ui_with_layout(
-                        egui::Vec2::new(ui.available_width(), ui.available_height() - 30.0),
+                        egui::Vec2::new(
+                            ui.available_width(),
+                            ui.available_height() - footer_height,
+                        ),
                         egui::Layout::top_down(egui::Align::default()),
                         |ui| {
                             let mut scroll_area = ScrollArea::new([true, true]).auto_shrink(false);
@@ -381,34 +1666,18 @@ int main(int argc, char **argv) {
 
                     ui.separator();
 
+                    if self.history.is_searching() {
+                        ui.horizontal(|ui| {
+                            ui.weak("reverse-i-search");
+                            match self.history.search_match() {
+                                Some(command) => ui.monospace(command),
+                                None => ui.weak("no match"),
+                            };
+                        });
+                    }
+
                     ui.horizontal(|ui| {
-                        ui.label("Command");
-
-                        // Reserve space for the controls at the end
-                        // TODO: Fix magic so it's dynamic
-                        let controls_width = ui.available_width() - 105.0;
-                        let response = ui.add_sized(
-                            [controls_width, ui.available_height()],
-                            TextEdit::singleline(&mut self.console_input)
-                                .font(TextStyle::Monospace),
-                        );
-
-                        if response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
-                            response.request_focus();
-                            if self.console_input.is_empty() {
-                                // Repeat last command
-                                if !self.console_input_prev.is_empty() {
-                                    self.add_pending_command(self.console_input_prev.clone());
-                                }
-                            } else {
-                                // Add command to pending queue for GDB processing
-                                self.add_pending_command(self.console_input.clone());
-                                if self.console_input_prev != self.console_input {
-                                    self.console_input_prev = self.console_input.clone();
-                                }
-                                self.console_input.clear();
-                            }
-                        };
+                        self.console_command_ui(ui);
 
                         ui.checkbox(&mut self.scroll_lock, "Scroll");
 
@@ -422,7 +1691,9 @@ int main(int argc, char **argv) {
                 ui.centered_and_justified(|ui| ui.heading("Exe"));
             }
             Tab::Breakpoints => {
-                ui.centered_and_justified(|ui| ui.heading("Breakpoints"));
+                for command in self.breakpoints.ui(ui) {
+                    self.query(Query::Breakpoint(command));
+                }
             }
             Tab::Commands => {
                 ui.centered_and_justified(|ui| ui.heading("Commands"));
@@ -431,29 +1702,69 @@ int main(int argc, char **argv) {
                 ui.centered_and_justified(|ui| ui.heading("Struct"));
             }
             Tab::Stack => {
-                ui.centered_and_justified(|ui| ui.heading("Stack"));
+                if let Some(action) = self.stack.ui(ui) {
+                    self.handle_stack_action(action);
+                }
             }
             Tab::Files => {
                 ui.centered_and_justified(|ui| ui.heading("Files"));
             }
             Tab::Thread => {
-                ui.centered_and_justified(|ui| ui.heading("Thread"));
+                if let Some(id) = self.threads.ui(ui) {
+                    self.query(Query::SelectThread(id));
+                }
             }
             Tab::CmdSearch => {
                 ui.centered_and_justified(|ui| ui.heading("CmdSearch"));
             }
             Tab::Watch => {
-                ui.centered_and_justified(|ui| ui.heading("Watch"));
+                ui.horizontal(|ui| {
+                    ui.label("Expression");
+                    let response = ui.add(
+                        TextEdit::singleline(&mut self.new_watch)
+                            .font(TextStyle::Monospace)
+                            .desired_width(ui.available_width() - 60.0),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+                    if (ui.button("Add").clicked() || submitted)
+                        && !self.new_watch.trim().is_empty()
+                    {
+                        let expression = self.new_watch.trim().to_owned();
+                        self.new_watch.clear();
+                        self.add_watch(expression);
+                    }
+                });
+
+                ui.separator();
+
+                let action = ScrollArea::both()
+                    .auto_shrink(false)
+                    .show(ui, |ui| self.watch_tree.ui(ui, "watch", true))
+                    .inner;
+                if let Some(action) = action {
+                    self.handle_var_action(action);
+                }
             }
             Tab::Locals => {
-                ui.centered_and_justified(|ui| ui.heading("Locals"));
+                let action = ScrollArea::both()
+                    .auto_shrink(false)
+                    .show(ui, |ui| self.locals.ui(ui, "locals", false))
+                    .inner;
+                if let Some(action) = action {
+                    self.handle_var_action(action);
+                }
             }
             Tab::Registers => {
-                ui.centered_and_justified(|ui| ui.heading("Registers"));
+                if let Some(action) = self.registers.ui(ui) {
+                    self.handle_register_action(action);
+                }
             }
             Tab::Data => {
-                ui.centered_and_justified(|ui| ui.heading("Data"));
+                let action = self.memory.ui(ui);
+                self.handle_memory_action(action);
             }
+            Tab::Output => self.output.ui(ui),
         }
     }
 }
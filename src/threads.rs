@@ -0,0 +1,130 @@
+use crate::mi::{Tuple, Value};
+use crate::stack::Frame;
+use egui::{Grid, RichText, ScrollArea, Ui};
+
+/// A thread of the inferior as reported by GDB
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thread {
+    pub id: String,
+    pub target_id: String,
+    pub name: Option<String>,
+    pub running: bool,
+    pub frame: Option<Frame>,
+}
+
+impl Thread {
+    /// Build a thread from an element of the `threads` list of `-thread-info`
+    pub fn from_tuple(thread: &Tuple) -> Option<Self> {
+        Some(Self {
+            id: thread.get_str("id")?.to_owned(),
+            target_id: thread.get_str("target-id").unwrap_or_default().to_owned(),
+            name: thread.get_str("name").map(str::to_owned),
+            running: thread.get_str("state") == Some("running"),
+            frame: thread.get_tuple("frame").and_then(Frame::from_tuple),
+        })
+    }
+}
+
+/// State of the Thread tab
+#[derive(Debug, Default)]
+pub struct Threads {
+    list: Vec<Thread>,
+    current: Option<String>,
+}
+
+impl Threads {
+    /// Replace all threads with the result of `-thread-info`
+    pub fn set_from_info(&mut self, threads: &[Value], current: Option<&str>) {
+        self.list = threads
+            .iter()
+            .filter_map(|t| t.as_tuple().and_then(Thread::from_tuple))
+            .collect();
+        self.current = current.map(str::to_owned);
+    }
+
+    /// Add a thread announced by `=thread-created`
+    pub fn add(&mut self, id: &str) {
+        if !self.list.iter().any(|t| t.id == id) {
+            self.list.push(Thread {
+                id: id.to_owned(),
+                target_id: String::new(),
+                name: None,
+                running: true,
+                frame: None,
+            });
+        }
+    }
+
+    /// Remove a thread announced by `=thread-exited`
+    pub fn remove(&mut self, id: &str) {
+        self.list.retain(|t| t.id != id);
+    }
+
+    /// Mark a thread, or `all` of them, as running
+    pub fn set_running(&mut self, id: &str) {
+        for thread in &mut self.list {
+            if id == "all" || thread.id == id {
+                thread.running = true;
+                thread.frame = None;
+            }
+        }
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.current.as_deref()
+    }
+
+    pub fn set_current(&mut self, id: &str) {
+        self.current = Some(id.to_owned());
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Render the thread list, returning the id of the thread to select
+    pub fn ui(&self, ui: &mut Ui) -> Option<String> {
+        let mut selected = None;
+
+        ScrollArea::both().auto_shrink(false).show(ui, |ui| {
+            Grid::new("threads")
+                .striped(true)
+                .num_columns(5)
+                .show(ui, |ui| {
+                    for header in ["Id", "Target Id", "Name", "State", "Frame"] {
+                        ui.label(RichText::new(header).strong());
+                    }
+                    ui.end_row();
+
+                    for thread in &self.list {
+                        let is_current = self.current.as_ref() == Some(&thread.id);
+                        if ui
+                            .selectable_label(is_current, RichText::new(&thread.id).monospace())
+                            .clicked()
+                            && !is_current
+                        {
+                            selected = Some(thread.id.clone());
+                        }
+                        ui.monospace(&thread.target_id);
+                        ui.label(thread.name.as_deref().unwrap_or(""));
+                        if thread.running {
+                            ui.label(RichText::new("running").color(ui.visuals().warn_fg_color));
+                        } else {
+                            ui.label("stopped");
+                        }
+                        match &thread.frame {
+                            Some(frame) => {
+                                ui.monospace(format!("{} {}", frame.function, frame.location()));
+                            }
+                            None => {
+                                ui.label("");
+                            }
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        selected
+    }
+}
@@ -0,0 +1,231 @@
+use crate::rr::{self, ReplayTarget, RrRecord, Trace, TraceProcess};
+use crate::session::Session;
+use egui::{Grid, RichText, ScrollArea, TextEdit, Ui};
+use std::path::{Path, PathBuf};
+
+/// State of the rr window, recording new traces and picking one to replay
+#[derive(Debug, Default)]
+pub struct TraceManager {
+    traces: Vec<Trace>,
+    /// Whether the trace list was read at least once
+    loaded: bool,
+    selected: Option<PathBuf>,
+    processes: Vec<TraceProcess>,
+    process: Option<u32>,
+    event: String,
+    record_args: String,
+    recorder: Option<RrRecord>,
+    status: Option<String>,
+}
+
+impl TraceManager {
+    pub fn is_recording(&self) -> bool {
+        self.recorder.is_some()
+    }
+
+    /// Read the trace list again
+    pub fn refresh(&mut self) {
+        self.loaded = true;
+        self.traces = match rr::trace_root().map(|root| rr::list_traces(&root)) {
+            Some(Ok(traces)) => traces,
+            Some(Err(e)) => {
+                self.status = Some(format!("Failed to list traces: {e}"));
+                Vec::new()
+            }
+            None => Vec::new(),
+        };
+
+        if self
+            .selected
+            .as_ref()
+            .is_some_and(|s| !self.traces.iter().any(|t| &t.path == s))
+        {
+            self.selected = None;
+            self.processes.clear();
+        }
+    }
+
+    /// Select a trace and list its processes
+    fn select(&mut self, path: PathBuf) {
+        self.processes = match rr::list_processes(&path) {
+            Ok(processes) => processes,
+            Err(e) => {
+                self.status = Some(format!("Failed to run rr ps: {e}"));
+                Vec::new()
+            }
+        };
+        self.process = None;
+        self.selected = Some(path);
+    }
+
+    /// Check if the recording finished, selecting its trace
+    pub fn poll(&mut self) {
+        let Some(status) = self.recorder.as_mut().and_then(RrRecord::try_finish) else {
+            return;
+        };
+
+        self.recorder = None;
+        self.status = Some(format!("Recording finished ({status})"));
+        self.refresh();
+        if let Some(latest) = self.traces.iter().find(|t| t.latest) {
+            self.select(latest.path.clone());
+        }
+    }
+
+    /// Render the window contents, returning the replay to start
+    pub fn ui(&mut self, ui: &mut Ui, executable: Option<&Path>) -> Option<Session> {
+        if !self.loaded {
+            self.refresh();
+        }
+
+        let mut request = None;
+
+        ui.heading("Record");
+        ui.horizontal(|ui| {
+            let name =
+                executable.map_or_else(|| "No executable".to_owned(), |e| e.display().to_string());
+            ui.monospace(name);
+            ui.add(
+                TextEdit::singleline(&mut self.record_args)
+                    .hint_text("arguments")
+                    .font(egui::TextStyle::Monospace),
+            );
+
+            match (&mut self.recorder, executable) {
+                (Some(recorder), _) => {
+                    ui.spinner();
+                    if ui.button("Stop").clicked()
+                        && let Err(e) = recorder.stop()
+                    {
+                        self.status = Some(format!("Failed to stop rr: {e}"));
+                    }
+                }
+                (None, Some(executable)) => {
+                    if ui.button("Record").clicked() {
+                        match RrRecord::spawn(executable, &self.record_args) {
+                            Ok(recorder) => {
+                                self.recorder = Some(recorder);
+                                self.status = Some("Recording...".to_owned());
+                            }
+                            Err(e) => self.status = Some(format!("Failed to run rr record: {e}")),
+                        }
+                    }
+                }
+                (None, None) => {
+                    ui.add_enabled(false, egui::Button::new("Record"));
+                }
+            }
+        });
+
+        if let Some(status) = &self.status {
+            ui.weak(status);
+        }
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.heading("Traces");
+            if ui.button("Refresh").clicked() {
+                self.refresh();
+            }
+        });
+
+        let mut select = None;
+        ScrollArea::vertical()
+            .id_salt("traces")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                if self.traces.is_empty() {
+                    ui.weak("No traces");
+                }
+                for trace in &self.traces {
+                    let mut text = trace.name.clone();
+                    if trace.latest {
+                        text.push_str(" (latest)");
+                    }
+                    let selected = self.selected.as_ref() == Some(&trace.path);
+                    if ui
+                        .selectable_label(selected, RichText::new(text).monospace())
+                        .on_hover_text(trace.path.display().to_string())
+                        .clicked()
+                        && !selected
+                    {
+                        select = Some(trace.path.clone());
+                    }
+                }
+            });
+        if let Some(path) = select {
+            self.select(path);
+        }
+
+        let Some(trace) = self.selected.clone() else {
+            return request;
+        };
+
+        ui.separator();
+        ui.heading("Processes");
+
+        ScrollArea::vertical()
+            .id_salt("trace_processes")
+            .max_height(160.0)
+            .show(ui, |ui| {
+                Grid::new("trace_processes")
+                    .striped(true)
+                    .num_columns(4)
+                    .show(ui, |ui| {
+                        for header in ["Pid", "Parent", "Exit", "Command"] {
+                            ui.label(RichText::new(header).strong());
+                        }
+                        ui.end_row();
+
+                        for process in &self.processes {
+                            let selected = self.process == Some(process.pid);
+                            if ui
+                                .selectable_label(
+                                    selected,
+                                    RichText::new(process.pid.to_string()).monospace(),
+                                )
+                                .on_hover_text("Replay this process, click again for the root")
+                                .clicked()
+                            {
+                                self.process = (!selected).then_some(process.pid);
+                            }
+                            ui.monospace(process.ppid.map(|p| p.to_string()).unwrap_or_default());
+                            ui.monospace(&process.exit);
+                            ui.monospace(&process.command);
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("Event");
+            ui.add(
+                TextEdit::singleline(&mut self.event)
+                    .hint_text("start")
+                    .desired_width(100.0),
+            );
+
+            let event = self.event.trim();
+            let parsed = event.parse().ok();
+            if !event.is_empty() && parsed.is_none() {
+                ui.colored_label(ui.visuals().error_fg_color, "Not an event number");
+            } else if ui.button("Replay").clicked() {
+                let targets = self
+                    .process
+                    .map(ReplayTarget::Process)
+                    .into_iter()
+                    .chain(parsed.map(ReplayTarget::Event))
+                    .collect();
+                request = Some(Session::Replay {
+                    trace: Some(trace),
+                    targets,
+                });
+            }
+        });
+
+        request
+    }
+}
@@ -1,5 +1,13 @@
+use crate::attach::AttachPicker;
+use crate::cli::Cli;
+use crate::controls::{ExecCommand, Keybindings};
 use crate::gdb::Gdb;
+use crate::launch::LaunchConfigs;
+use crate::profiles::{PROJECT_FILE, Project};
+use crate::remote::{ConnectDialog, RemoteConnection, RemoteTarget};
+use crate::session::Session;
 use crate::tabs::{Tab, Tabs};
+use crate::traces::TraceManager;
 use egui::{Color32, MenuBar, RichText, TopBottomPanel};
 use egui_dock::{DockArea, DockState, Style};
 use serde::{Deserialize, Serialize};
@@ -13,6 +21,12 @@ use std::{
 pub struct UiManager {
     dock_state: DockState<Tab>,
     tabs: Tabs,
+    #[serde(default)]
+    keybindings: Keybindings,
+    #[serde(default)]
+    connect: ConnectDialog,
+    #[serde(default)]
+    launch: LaunchConfigs,
 
     #[serde(skip)]
     picked_file: Option<PathBuf>,
@@ -23,6 +37,32 @@ pub struct UiManager {
 
     #[serde(skip)]
     file_just_loaded: bool,
+    #[serde(skip)]
+    show_keybindings: bool,
+    /// Whether GDB is connected to a target that can execute backwards
+    #[serde(skip)]
+    reverse_available: bool,
+    #[serde(skip)]
+    traces: TraceManager,
+    #[serde(skip)]
+    show_traces: bool,
+    #[serde(skip)]
+    attach: AttachPicker,
+    #[serde(skip)]
+    show_attach: bool,
+    #[serde(skip)]
+    show_connect: bool,
+    #[serde(skip)]
+    show_launch: bool,
+    /// Environment variables set in the running GDB by the launch settings
+    #[serde(skip)]
+    applied_env: Vec<String>,
+    /// The project file of the working directory
+    #[serde(skip)]
+    project: Option<Project>,
+    /// A session picked in a window, started by the app on the next update
+    #[serde(skip)]
+    session_request: Option<Session>,
 
     pub zoom: f32,
     #[serde(skip)]
@@ -34,12 +74,26 @@ impl Default for UiManager {
         Self {
             dock_state: Self::setup_dock_layout(),
             tabs: Tabs::default(),
+            keybindings: Keybindings::default(),
+            connect: ConnectDialog::default(),
+            launch: LaunchConfigs::default(),
 
             picked_file: None,
             picked_path_file_name: String::new(),
             picked_path_full_path: String::new(),
 
             file_just_loaded: false,
+            show_keybindings: false,
+            reverse_available: false,
+            traces: TraceManager::default(),
+            show_traces: false,
+            attach: AttachPicker::default(),
+            show_attach: false,
+            show_connect: false,
+            show_launch: false,
+            applied_env: Vec::new(),
+            project: None,
+            session_request: None,
 
             zoom: 1.0,
             zoom_temp: 1.0,
@@ -85,30 +139,73 @@ impl UiManager {
                         self.set_file_details(&path);
                     }
 
+                    if ui
+                        .add_enabled(
+                            self.picked_file.is_some(),
+                            egui::Button::new("Launch Settings"),
+                        )
+                        .on_disabled_hover_text("Open an executable first")
+                        .clicked()
+                    {
+                        self.show_launch = true;
+                    }
+
+                    if ui
+                        .button("Open Core")
+                        .on_hover_text("Inspect a core dump of the open executable")
+                        .clicked()
+                        && let Some(core) =
+                            rfd::FileDialog::new().set_title("Open Core").pick_file()
+                    {
+                        self.session_request = Some(Session::Core {
+                            executable: self.picked_file.clone(),
+                            core,
+                        });
+                    }
+
+                    if ui.button("Attach").clicked() {
+                        self.show_attach = true;
+                        self.attach.refresh();
+                    }
+
+                    if ui.button("Connect").clicked() {
+                        self.show_connect = true;
+                    }
+
+                    if ui.button("rr Traces").clicked() {
+                        self.show_traces = true;
+                        self.traces.refresh();
+                    }
+
+                    ui.menu_button("Profiles", |ui| {
+                        let names: Vec<String> = self
+                            .project
+                            .iter()
+                            .flat_map(|p| p.profiles.keys().cloned())
+                            .collect();
+                        if names.is_empty() {
+                            ui.weak(format!("No profiles in {PROJECT_FILE}"));
+                        }
+                        for name in names {
+                            if ui.button(&name).clicked() {
+                                self.apply_profile(&name);
+                            }
+                        }
+
+                        ui.separator();
+
+                        if ui.button(format!("Reload {PROJECT_FILE}")).clicked() {
+                            self.load_project();
+                        }
+                    });
+
                     if ui.button("Quit").clicked() {
                         ctx.send_viewport_cmd(egui::ViewportCommand::Close);
                     }
                 });
 
                 ui.menu_button("Windows", |ui| {
-                    let tabs = [
-                        Tab::Source,
-                        Tab::Console,
-                        Tab::Exe,
-                        Tab::Breakpoints,
-                        Tab::Commands,
-                        Tab::Struct,
-                        Tab::Stack,
-                        Tab::Files,
-                        Tab::Thread,
-                        Tab::CmdSearch,
-                        Tab::Watch,
-                        Tab::Locals,
-                        Tab::Registers,
-                        Tab::Data,
-                    ];
-
-                    for tab in tabs {
+                    for tab in Tab::ALL {
                         let is_visible = self.is_tab_visible(&tab);
                         let mut visible = is_visible;
 
@@ -122,8 +219,42 @@ impl UiManager {
                     if ui.button("Reset Layout").clicked() {
                         self.dock_state = Self::setup_dock_layout();
                     }
+
+                    if ui.button("Keybindings").clicked() {
+                        self.show_keybindings = true;
+                    }
                 });
 
+                ui.separator();
+
+                for command in ExecCommand::ALL {
+                    if command.is_reverse() && !self.reverse_available {
+                        continue;
+                    }
+                    if command == ExecCommand::ReverseContinue {
+                        ui.separator();
+                    }
+
+                    let hover = match self.keybindings.shortcut_text(ctx, command) {
+                        Some(shortcut) => format!("{} ({shortcut})", command.label()),
+                        None => command.label().to_owned(),
+                    };
+                    if ui
+                        .add_enabled(self.allows(command), egui::Button::new(command.icon()))
+                        .on_hover_text(hover)
+                        .clicked()
+                    {
+                        self.exec(command);
+                    }
+                }
+
+                if let Some(remote) = self.tabs.remote() {
+                    ui.separator();
+                    if remote.ui(ui) {
+                        self.tabs.reconnect_remote();
+                    }
+                }
+
                 if self.picked_file.is_some() {
                     ui.centered_and_justified(|ui| {
                         ui.label(&self.picked_path_file_name)
@@ -159,6 +290,18 @@ impl UiManager {
         });
     }
 
+    pub fn show_status_bar(&mut self, ctx: &egui::Context) {
+        TopBottomPanel::bottom("status").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if self.tabs.is_gdb_available() {
+                    self.tabs.inferior().ui(ui);
+                } else {
+                    ui.weak("GDB not running");
+                }
+            });
+        });
+    }
+
     pub fn update(&mut self, ctx: &egui::Context) {
         ctx.input(|i| {
             if let Some(file) = i.raw.dropped_files.first()
@@ -167,15 +310,205 @@ impl UiManager {
                 self.set_file_details(path);
             }
         });
+
+        if let Some(command) = self.keybindings.pressed(ctx)
+            && self.allows(command)
+        {
+            self.exec(command);
+        }
+
+        self.traces.poll();
+        if self.traces.is_recording()
+            || self.tabs.remote().is_some_and(RemoteConnection::is_waiting)
+        {
+            ctx.request_repaint_after(std::time::Duration::from_millis(250));
+        }
+
+        let mut replay = None;
+        egui::Window::new("rr Traces")
+            .open(&mut self.show_traces)
+            .show(ctx, |ui| {
+                replay = self.traces.ui(ui, self.picked_file.as_deref());
+            });
+        if replay.is_some() {
+            self.session_request = replay;
+            self.show_traces = false;
+        }
+
+        let mut attach = None;
+        egui::Window::new("Attach")
+            .open(&mut self.show_attach)
+            .show(ctx, |ui| attach = self.attach.ui(ui));
+        if let Some(pid) = attach {
+            self.session_request = Some(Session::Attach(pid));
+            self.show_attach = false;
+        }
+
+        let mut connect = None;
+        egui::Window::new("Connect to gdbserver")
+            .open(&mut self.show_connect)
+            .resizable(false)
+            .show(ctx, |ui| connect = self.connect.ui(ui));
+        if let Some(target) = connect {
+            self.session_request = Some(Session::Remote {
+                executable: self.picked_file.clone(),
+                target,
+            });
+            self.show_connect = false;
+        }
+
+        egui::Window::new("Launch Settings")
+            .open(&mut self.show_launch)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.monospace(&self.picked_path_full_path);
+                ui.separator();
+                self.launch.get_mut(&self.picked_path_full_path).ui(ui);
+            });
+
+        egui::Window::new("Keybindings")
+            .open(&mut self.show_keybindings)
+            .resizable(false)
+            .show(ctx, |ui| self.keybindings.ui(ui));
+    }
+
+    /// Check if an execution control command can be sent right now
+    fn allows(&self, command: ExecCommand) -> bool {
+        (!command.is_reverse() || self.reverse_available) && self.tabs.allows(command)
+    }
+
+    /// Set whether the reverse execution controls are shown
+    pub fn set_reverse_available(&mut self, available: bool) {
+        self.reverse_available = available;
+    }
+
+    /// Queue a command for GDB, logged like a console command
+    pub fn send_command(&mut self, command: String) {
+        self.tabs.add_pending_command(command);
+    }
+
+    /// Send an execution control command, logged like a console command
+    fn exec(&mut self, command: ExecCommand) {
+        if matches!(command, ExecCommand::Run | ExecCommand::Restart)
+            && let Some(config) = self.launch.get(&self.picked_path_full_path)
+        {
+            for launch_command in config.commands(&self.applied_env) {
+                self.tabs.add_pending_command(launch_command);
+            }
+            self.applied_env = config.env_names();
+        }
+
+        for mi_command in command.mi_commands() {
+            self.tabs.add_pending_command((*mi_command).to_owned());
+        }
     }
 
     pub fn set_file_details(&mut self, path: &Path) {
+        self.set_picked_file(path);
+        self.file_just_loaded = true;
+    }
+
+    /// Show the executable of a session without starting GDB for it
+    pub fn set_picked_file(&mut self, path: &Path) {
         self.picked_file = Some(path.to_path_buf());
         self.picked_path_full_path = path.display().to_string();
         if let Some(file_name) = path.file_name() {
             self.picked_path_file_name = file_name.display().to_string();
         }
-        self.file_just_loaded = true;
+        self.tabs.set_executable(path);
+    }
+
+    /// Save the launch settings given on the command line for an executable
+    pub fn apply_cli_launch(&mut self, path: &Path, cli: &Cli) {
+        self.launch
+            .get_mut(&path.display().to_string())
+            .apply_cli(cli);
+    }
+
+    /// Read the project file of the working directory
+    pub fn load_project(&mut self) {
+        let project = std::env::current_dir()
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|dir| Project::load(&dir));
+        match project {
+            Ok(Some(project)) => {
+                self.add_info(format!(
+                    "Loaded {} profiles from {PROJECT_FILE}",
+                    project.profiles.len()
+                ));
+                self.project = Some(project);
+            }
+            Ok(None) => self.project = None,
+            Err(e) => self.add_error(format!("Failed to load {PROJECT_FILE}: {e}")),
+        }
+    }
+
+    /// Open the executable of a profile with its settings, breakpoints and layout
+    pub fn apply_profile(&mut self, name: &str) {
+        let Some(project) = &self.project else {
+            self.add_error(format!("No {PROJECT_FILE} in the working directory"));
+            return;
+        };
+        let Some(profile) = project.profiles.get(name).cloned() else {
+            self.add_error(format!("No profile named {name} in {PROJECT_FILE}"));
+            return;
+        };
+        let Some(executable) = project
+            .executable(&profile)
+            .or_else(|| self.picked_file.clone())
+        else {
+            self.add_error(format!("Profile {name} has no executable"));
+            return;
+        };
+
+        let key = executable.display().to_string();
+        *self.launch.get_mut(&key) = profile.launch_config();
+        if !profile.watches.is_empty() {
+            self.tabs.set_watches(&key, &profile.watches);
+        }
+        if !profile.tabs.is_empty() {
+            for tab in Tab::ALL {
+                let visible = profile.tabs.contains(&tab);
+                self.set_tab_visible(tab, visible);
+            }
+        }
+
+        // Queued until the GDB started for the executable is available
+        self.set_file_details(&executable);
+        for location in &profile.breakpoints {
+            self.tabs.insert_breakpoint(location);
+        }
+        for command in profile.gdb_commands {
+            self.tabs.add_pending_command(command);
+        }
+        self.add_info(format!("Using profile {name}"));
+    }
+
+    /// Inspect the core dump GDB was started with
+    pub fn load_core(&mut self) {
+        self.tabs.load_core();
+    }
+
+    /// Connect GDB to a gdbserver
+    pub fn connect_remote(&mut self, target: RemoteTarget) {
+        self.tabs.connect_remote(target);
+    }
+
+    /// Stop reconnecting to the gdbserver
+    pub fn disable_remote_reconnect(&mut self) {
+        self.tabs.disable_remote_reconnect();
+    }
+
+    /// Check if GDB is connected to a gdbserver
+    pub fn is_remote_connected(&self) -> bool {
+        self.tabs
+            .remote()
+            .is_some_and(RemoteConnection::is_connected)
+    }
+
+    /// Take the session picked in a window to be started
+    pub fn take_session_request(&mut self) -> Option<Session> {
+        self.session_request.take()
     }
 
     /// Get the currently picked file path
@@ -204,6 +537,11 @@ impl UiManager {
         self.tabs.update_from_gdb(gdb);
     }
 
+    /// Whether queries are waiting to be sent or for their results
+    pub fn is_busy(&self) -> bool {
+        self.tabs.is_busy()
+    }
+
     /// Send a command to GDB through tabs
     pub fn send_command_to_gdb(&mut self, command: &str, gdb: &Gdb) -> Result<(), Box<dyn Error>> {
         self.tabs.send_command_to_gdb(command, gdb)
@@ -219,8 +557,10 @@ impl UiManager {
     }
 
     /// Set GDB availability in tabs
-    pub fn set_gdb_available(&mut self, available: bool) {
-        self.tabs.set_gdb_available(available);
+    pub fn set_gdb_available(&mut self, available: bool, ctx: &egui::Context) {
+        // A new GDB starts from its own environment
+        self.applied_env.clear();
+        self.tabs.set_gdb_available(available, ctx);
     }
 
     /// Add an info message to the console
@@ -269,11 +609,14 @@ impl UiManager {
     }
 
     fn setup_dock_layout() -> DockState<Tab> {
-        let mut dock_state = DockState::<Tab>::new(vec![Tab::Source]);
+        let mut dock_state = DockState::<Tab>::new(vec![Tab::Source, Tab::Disassembly]);
         let surface = dock_state.main_surface_mut();
 
-        let [center, bottom_left] =
-            surface.split_below(egui_dock::NodeIndex::root(), 0.6666666, vec![Tab::Console]);
+        let [center, bottom_left] = surface.split_below(
+            egui_dock::NodeIndex::root(),
+            0.6666666,
+            vec![Tab::Console, Tab::Output],
+        );
 
         let [_, _bottom_right] = surface.split_right(
             bottom_left,
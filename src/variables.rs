@@ -0,0 +1,415 @@
+use crate::mi::{Tuple, Value};
+use egui::{Grid, Key, RichText, TextEdit, Ui};
+use serde::{Deserialize, Serialize};
+
+/// How the value of a variable object is displayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum DisplayFormat {
+    #[default]
+    Natural,
+    Hexadecimal,
+    Decimal,
+    Binary,
+    Char,
+}
+
+impl DisplayFormat {
+    pub const ALL: [Self; 5] = [
+        Self::Natural,
+        Self::Hexadecimal,
+        Self::Decimal,
+        Self::Binary,
+        Self::Char,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Natural => "Natural",
+            Self::Hexadecimal => "Hex",
+            Self::Decimal => "Decimal",
+            Self::Binary => "Binary",
+            Self::Char => "Char",
+        }
+    }
+
+    /// The `-var-set-format` argument, `None` for formats GDB has no var-object format for
+    pub fn mi_format(self) -> Option<&'static str> {
+        match self {
+            Self::Natural => Some("natural"),
+            Self::Hexadecimal => Some("hexadecimal"),
+            Self::Decimal => Some("decimal"),
+            Self::Binary => Some("binary"),
+            Self::Char => None,
+        }
+    }
+
+    /// The expression to create the variable object for, casting for [`Self::Char`]
+    pub fn wrap_expression(self, expression: &str) -> String {
+        match self {
+            Self::Char => format!("(char)({expression})"),
+            _ => expression.to_owned(),
+        }
+    }
+}
+
+/// A watch expression, persisted per executable
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct WatchExpression {
+    pub expression: String,
+    pub format: DisplayFormat,
+}
+
+/// A GDB variable object, shown as a node of a variable tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VarObj {
+    /// Name of the variable object in GDB, e.g. `var1.x`
+    pub name: String,
+    /// Expression shown to the user
+    pub expression: String,
+    pub value: String,
+    pub type_name: String,
+    pub num_children: usize,
+    /// `None` until the children have been fetched
+    pub children: Option<Vec<Self>>,
+    pub expanded: bool,
+    /// Whether the value changed since the last stop
+    pub changed: bool,
+    pub in_scope: bool,
+    pub format: DisplayFormat,
+}
+
+impl VarObj {
+    /// Build a variable object from the result of `-var-create` or a `child` tuple
+    pub fn from_tuple(var: &Tuple, expression: Option<&str>) -> Option<Self> {
+        let num_children = var
+            .get_str("numchild")
+            .and_then(|n| n.parse().ok())
+            .unwrap_or(0);
+        let has_more = var.get_str("has_more").is_some_and(|m| m != "0");
+
+        Some(Self {
+            name: var.get_str("name")?.to_owned(),
+            expression: expression
+                .or_else(|| var.get_str("exp"))
+                .unwrap_or_default()
+                .to_owned(),
+            value: var.get_str("value").unwrap_or_default().to_owned(),
+            type_name: var.get_str("type").unwrap_or_default().to_owned(),
+            num_children: if has_more {
+                num_children.max(1)
+            } else {
+                num_children
+            },
+            children: None,
+            expanded: false,
+            changed: false,
+            in_scope: true,
+            format: DisplayFormat::Natural,
+        })
+    }
+
+    /// A root without a variable object, for an expression GDB could not evaluate
+    pub fn placeholder(expression: &str, message: &str, format: DisplayFormat) -> Self {
+        Self {
+            name: String::new(),
+            expression: expression.to_owned(),
+            value: message.to_owned(),
+            type_name: String::new(),
+            num_children: 0,
+            children: None,
+            expanded: false,
+            changed: false,
+            in_scope: false,
+            format,
+        }
+    }
+
+    /// Whether GDB has a variable object for this node
+    pub fn is_placeholder(&self) -> bool {
+        self.name.is_empty()
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut Self> {
+        if self.name == name {
+            return Some(self);
+        }
+        // Children are named after their parent, e.g. `var1.x.y`, and `var10` is not one of `var1`
+        if !name
+            .strip_prefix(self.name.as_str())
+            .is_some_and(|rest| rest.starts_with('.'))
+        {
+            return None;
+        }
+        self.children
+            .as_mut()?
+            .iter_mut()
+            .find_map(|child| child.find_mut(name))
+    }
+
+    fn clear_changed(&mut self) {
+        self.changed = false;
+        for child in self.children.iter_mut().flatten() {
+            child.clear_changed();
+        }
+    }
+}
+
+/// An action requested from a variable tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VarAction {
+    /// Fetch the children of a variable object
+    Expand(String),
+    /// Assign a new value to a variable object
+    Assign(String, String),
+    /// Remove the root with the given expression
+    Remove(String),
+    /// Change the display format of the root with the given expression
+    SetFormat(String, DisplayFormat),
+}
+
+#[derive(Debug, Clone)]
+struct VarEdit {
+    name: String,
+    value: String,
+}
+
+/// A tree of variable objects with name, type and value columns
+#[derive(Debug, Default)]
+pub struct VarTree {
+    roots: Vec<VarObj>,
+    edit: Option<VarEdit>,
+}
+
+impl VarTree {
+    pub fn roots(&self) -> &[VarObj] {
+        &self.roots
+    }
+
+    pub fn add_root(&mut self, var: VarObj) {
+        self.roots.push(var);
+    }
+
+    /// Remove all variable objects, returning the names of the roots
+    pub fn clear(&mut self) -> Vec<String> {
+        self.edit = None;
+        self.roots.drain(..).map(|r| r.name).collect()
+    }
+
+    /// Check if a variable object, or one of its roots, is part of this tree
+    pub fn contains(&self, name: &str) -> bool {
+        self.roots.iter().any(|r| {
+            name == r.name
+                || name
+                    .strip_prefix(r.name.as_str())
+                    .is_some_and(|rest| rest.starts_with('.'))
+        })
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut VarObj> {
+        if name.is_empty() {
+            return None;
+        }
+        self.roots.iter_mut().find_map(|r| r.find_mut(name))
+    }
+
+    /// Replace the root with the same expression, or add it at the end
+    pub fn replace_root(&mut self, var: VarObj) {
+        match self
+            .roots
+            .iter_mut()
+            .find(|r| r.expression == var.expression)
+        {
+            Some(root) => *root = var,
+            None => self.roots.push(var),
+        }
+    }
+
+    /// Remove the root with the given expression, returning it
+    pub fn remove_expression(&mut self, expression: &str) -> Option<VarObj> {
+        let index = self.roots.iter().position(|r| r.expression == expression)?;
+        Some(self.roots.remove(index))
+    }
+
+    /// Find the root with the given expression
+    pub fn root_by_expression_mut(&mut self, expression: &str) -> Option<&mut VarObj> {
+        self.roots.iter_mut().find(|r| r.expression == expression)
+    }
+
+    /// Set the children of a variable object from the result of `-var-list-children`
+    pub fn set_children(&mut self, name: &str, children: &[Value]) {
+        if let Some(var) = self.find_mut(name) {
+            var.children = Some(
+                children
+                    .iter()
+                    .filter_map(|c| c.as_tuple().and_then(|c| VarObj::from_tuple(c, None)))
+                    .collect(),
+            );
+        }
+    }
+
+    /// Set the value of a variable object
+    pub fn set_value(&mut self, name: &str, value: &str) {
+        if let Some(var) = self.find_mut(name) {
+            var.value = value.to_owned();
+        }
+    }
+
+    /// Forget the changes highlighted since the previous stop
+    pub fn clear_changed(&mut self) {
+        for root in &mut self.roots {
+            root.clear_changed();
+        }
+    }
+
+    /// Apply a `changelist` from `-var-update`, returning the roots that went out of scope
+    pub fn apply_update(&mut self, changelist: &[Value]) -> Vec<String> {
+        let mut out_of_scope = Vec::new();
+
+        for change in changelist.iter().filter_map(Value::as_tuple) {
+            let Some(name) = change.get_str("name") else {
+                continue;
+            };
+            let Some(var) = self.find_mut(name) else {
+                continue;
+            };
+
+            var.changed = true;
+            if let Some(value) = change.get_str("value") {
+                var.value = value.to_owned();
+            }
+            if let Some(type_name) = change.get_str("new_type") {
+                var.type_name = type_name.to_owned();
+            }
+            if change.get_str("type_changed") == Some("true")
+                || change.get_str("new_num_children").is_some()
+            {
+                if let Some(n) = change
+                    .get_str("new_num_children")
+                    .and_then(|n| n.parse().ok())
+                {
+                    var.num_children = n;
+                }
+                var.children = None;
+                var.expanded = false;
+            }
+
+            var.in_scope = change.get_str("in_scope") != Some("false");
+            if !var.in_scope {
+                var.value = "<out of scope>".to_owned();
+                out_of_scope.push(name.to_owned());
+            }
+        }
+
+        out_of_scope
+    }
+
+    /// Render the tree, returning the requested action
+    ///
+    /// With `root_controls` the roots get a context menu to change their format or remove them.
+    pub fn ui(&mut self, ui: &mut Ui, id_salt: &str, root_controls: bool) -> Option<VarAction> {
+        let mut action = None;
+        let Self { roots, edit } = self;
+
+        Grid::new(id_salt)
+            .striped(true)
+            .num_columns(3)
+            .show(ui, |ui| {
+                for header in ["Name", "Type", "Value"] {
+                    ui.label(RichText::new(header).strong());
+                }
+                ui.end_row();
+
+                for root in roots.iter_mut() {
+                    node_ui(ui, root, 0, root_controls, edit, &mut action);
+                }
+            });
+
+        action
+    }
+}
+
+fn node_ui(
+    ui: &mut Ui,
+    var: &mut VarObj,
+    depth: usize,
+    root_controls: bool,
+    edit: &mut Option<VarEdit>,
+    action: &mut Option<VarAction>,
+) {
+    ui.horizontal(|ui| {
+        ui.add_space(depth as f32 * 12.0);
+        if var.num_children > 0 {
+            let icon = if var.expanded { "▼" } else { "▶" };
+            if ui.small_button(RichText::new(icon).monospace()).clicked() {
+                var.expanded = !var.expanded;
+                if var.expanded && var.children.is_none() {
+                    *action = Some(VarAction::Expand(var.name.clone()));
+                }
+            }
+        }
+        let response = ui.monospace(&var.expression);
+        if root_controls {
+            response
+                .on_hover_text("Right-click to change the format or remove")
+                .context_menu(|ui| {
+                    for format in DisplayFormat::ALL {
+                        if ui.radio(var.format == format, format.label()).clicked() {
+                            *action = Some(VarAction::SetFormat(var.expression.clone(), format));
+                            ui.close();
+                        }
+                    }
+                    ui.separator();
+                    if ui.button("Remove").clicked() {
+                        *action = Some(VarAction::Remove(var.expression.clone()));
+                        ui.close();
+                    }
+                });
+        }
+    });
+
+    ui.label(RichText::new(&var.type_name).monospace().weak());
+
+    match edit {
+        Some(e) if e.name == var.name => {
+            let response =
+                ui.add(TextEdit::singleline(&mut e.value).font(egui::TextStyle::Monospace));
+            if response.lost_focus() {
+                if ui.input(|i| i.key_pressed(Key::Enter)) {
+                    *action = Some(VarAction::Assign(e.name.clone(), e.value.clone()));
+                }
+                *edit = None;
+            } else {
+                response.request_focus();
+            }
+        }
+        _ => {
+            let mut value = RichText::new(&var.value).monospace();
+            if var.changed {
+                value = value.color(ui.visuals().warn_fg_color);
+            }
+            if !var.in_scope {
+                value = value.weak().italics();
+            }
+            let response = ui.label(value);
+            if var.num_children == 0
+                && var.in_scope
+                && !var.is_placeholder()
+                && response
+                    .on_hover_text("Double-click to edit")
+                    .double_clicked()
+            {
+                *edit = Some(VarEdit {
+                    name: var.name.clone(),
+                    value: var.value.clone(),
+                });
+            }
+        }
+    }
+    ui.end_row();
+
+    if var.expanded {
+        for child in var.children.iter_mut().flatten() {
+            node_ui(ui, child, depth + 1, false, edit, action);
+        }
+    }
+}